@@ -1,19 +1,58 @@
+/// popped groups
+use crate::archive::{archive_groups, archive_page, ArchiveView};
 /// exacute a tuckr command
-use crate::cmd::run;
+use crate::cmd::{run, HELP};
+/// targets in the way of add and set
+use crate::conflicts::{conflict_dialog, find_conflicts, resolve, Conflict, ConflictDialog};
+/// repo dotfile next to its deployed target
+use crate::diff::{diff_window, DiffView};
+/// tabs of the hook editer
+use crate::editor::{code_editer, unsaved_dialog, Editor};
+/// why the groups couldn't be loaded
+use crate::error::{error_message, UiError};
 /// dnd file pickers
-use crate::filepicker::{hook_file_picker, push_file_picker};
+use crate::filepicker::{encrypt_file_picker, hook_file_picker, push_file_picker};
+/// load groups in the background
+use crate::groups::{
+	create_group, default_exclude, dotfiles_dir, find_groups, group_os, is_valid_target, new_group_name, LoadedGroups,
+	OS_SUFFIXES,
+};
+/// commands that were run
+use crate::history::{history_page, page_of, HistoryEntry};
+/// hooks overview
+use crate::hooks::{create_hook, hook_log, hooks_browser, open_hook, run_set, HookLog, HooksView};
+/// undo the last operation
+use crate::journal::{journal, undo_button, undo_window, Operation, Snapshot};
+use crate::loader::Loader;
+/// which group owns a file
+use crate::lookup::{group_lookup, GroupLookup};
+/// dry run of add
+use crate::plan::{add_preview, AddPreview};
+/// saved group selections
+use crate::profiles::{profile_bar, switch_profile, Profile};
+/// passphrase prompt for secrets
+use crate::secrets::{passphrase_prompt, restored_secrets, secrets_inventory, SecretsView};
+/// symlink status table
+use crate::status::{status_table, StatusView};
+/// import a stow repo
+use crate::stow::{stow_wizard, StowWizard};
+/// files of a group
+use crate::tree::{group_tree, GroupTree};
+/// reload what changed in the dotfiles
+use crate::watcher::{Changed, DotfilesWatcher};
+/// run commands off the ui thread
+use crate::worker::{job_progress, poll_job, Cancel, Job};
 use egui::{include_image, Image, KeyboardShortcut, Modifiers};
-use egui::{Button, Color32, DroppedFile, Ui};
+use egui::{Button, Color32, RichText, Ui};
 use egui_multiselect::MultiSelect;
 use std::fmt::Display;
-use std::fs;
 use std::path::PathBuf;
-use std::thread::{self, JoinHandle};
-use tuckr::dotfiles::ReturnCode;
+use std::process::ExitCode;
+use std::sync::mpsc;
 /// the tuckr state
 use tuckr::Cli;
 
-#[derive(Default, serde::Deserialize, serde::Serialize, PartialEq)]
+#[derive(Default, serde::Deserialize, serde::Serialize, PartialEq, Clone, Copy)]
 pub enum HookType {
 	Pre,
 	#[default]
@@ -49,6 +88,22 @@ pub enum Page {
 	Init,
 	/// create and edit hooks
 	Hooks,
+	/// files to encrypt
+	Encrypt(Option<Vec<String>>),
+	/// exclude
+	Decrypt(Option<Vec<String>>),
+	/// convert a GNU Stow repo
+	FromStow,
+	/// files to find the group of
+	GroupIs(Option<Vec<String>>),
+	/// stored secrets
+	Secrets,
+	/// files of a group
+	Files,
+	/// commands that were run
+	History,
+	/// popped groups
+	Archive,
 }
 
 impl Page {
@@ -56,8 +111,13 @@ impl Page {
 	/// `\*` is all groups, None if page is help
 	pub fn into_cli(self, groups: Vec<String>) -> Result<Cli, String> {
 		match self {
-			Page::Help => Err(include_str!("../assets/help.txt").to_string()),
-			Page::Status => Ok(Cli::Status { groups: None }),
+			Page::Help => Err(HELP.to_string()),
+			Page::Status => Ok(Cli::Status {
+				groups: match groups.iter().any(|g| g == r"\*") {
+					true => None,
+					false => Some(groups),
+				},
+			}),
 			// use combobox for exclude and something groups
 			Page::Add(exclude, force, adopt) => Ok(Cli::Add {
 				groups,
@@ -76,19 +136,49 @@ impl Page {
 				adopt,
 			}),
 			Page::Push(f) => Ok(Cli::Push {
-				group: groups[0].clone(),
+				group: single_group(&groups)?,
 				files: match f {
-					Some(ps) => ps,
-					None => return Err("select a path".into()),
+					Some(ps) if !ps.is_empty() => ps,
+					_ => return Err("select a path".into()),
 				},
 			}),
 			Page::Pop => Ok(Cli::Pop { groups }),
 			Page::Init => Ok(Cli::Init),
 			Page::Hooks => Err("editer".into()),
+			Page::FromStow => Err("wizard".into()),
+			Page::Secrets => Ok(Cli::LsSecrets),
+			Page::Files => Err("browser".into()),
+			Page::History => Err("history".into()),
+			Page::Archive => Err("archive".into()),
+			Page::GroupIs(f) => Ok(Cli::GroupIs {
+				files: match f {
+					Some(fs) if !fs.is_empty() => fs,
+					_ => return Err("select a file to look up".into()),
+				},
+			}),
+			Page::Encrypt(f) => Ok(Cli::Encrypt {
+				group: single_group(&groups)?,
+				dotfiles: match f {
+					Some(fs) if !fs.is_empty() => fs,
+					_ => return Err("select a file to encrypt".into()),
+				},
+			}),
+			Page::Decrypt(exclude) => Ok(Cli::Decrypt {
+				groups,
+				exclude: exclude.unwrap_or_default(),
+			}),
 		}
 	}
 }
 
+/// The group push and encrypt use, they can't be run on all groups
+fn single_group(groups: &[String]) -> Result<String, String> {
+	match groups.first() {
+		Some(group) if group != r"\*" => Ok(group.clone()),
+		_ => Err("select a group".into()),
+	}
+}
+
 impl Display for Page {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
@@ -101,6 +191,14 @@ impl Display for Page {
 			Page::Set(_, _, _) => write!(f, "Set"),
 			Page::Status => write!(f, "Status"),
 			Page::Hooks => write!(f, "Hooks"),
+			Page::Encrypt(_) => write!(f, "Encrypt"),
+			Page::Decrypt(_) => write!(f, "Decrypt"),
+			Page::FromStow => write!(f, "From Stow"),
+			Page::GroupIs(_) => write!(f, "Group is"),
+			Page::Secrets => write!(f, "Secrets"),
+			Page::Files => write!(f, "Files"),
+			Page::History => write!(f, "History"),
+			Page::Archive => write!(f, "Archive"),
 		}
 	}
 }
@@ -111,39 +209,123 @@ impl Display for Page {
 pub struct TemplateApp {
 	/// if the adopt flag is used on add and set
 	#[serde(skip)]
-	adopt: bool,
-	/// timer between checking groups
+	pub adopt: bool,
+	/// load the groups on the next frame
+	#[serde(skip)]
+	pub reload_groups: bool,
+	/// Loads the groups off the ui thread
 	#[serde(skip)]
-	pub check_count: u32,
+	group_loader: Loader<LoadedGroups>,
+	/// Watches the dotfiles for changes
+	#[serde(skip)]
+	pub watcher: Option<DotfilesWatcher>,
 	/// exclude
-	exclude: Option<Vec<String>>,
+	pub exclude: Option<Vec<String>>,
 	/// if the force flag is used on add and set
 	#[serde(skip)]
-	force: bool,
+	pub force: bool,
+	/// Groups that were popped
+	#[serde(skip)]
+	pub archive: ArchiveView,
+	/// Archived group waiting for the user to confirm deleting it
+	#[serde(skip)]
+	pub archive_delete: Option<(String, PathBuf)>,
+	/// Changes of the last commands, newest last
+	pub journal: Vec<Operation>,
+	/// Show what undo would restore
+	#[serde(skip)]
+	pub undo_preview: bool,
+	/// Commands that were run with their output
+	pub history: Vec<HistoryEntry>,
+	/// Saved group selections
+	pub profiles: Vec<Profile>,
+	/// The selected profile
+	pub profile: Option<String>,
+	/// Name for a new profile
+	#[serde(skip)]
+	pub profile_name: String,
 	/// Avalibule groups
 	#[serde(skip)]
-	found_groups: Option<Vec<String>>,
+	pub found_groups: Option<Vec<String>>,
+	/// Why the groups couldn't be loaded
+	#[serde(skip)]
+	groups_error: Option<UiError>,
+	/// Groups that were found but can't be used
+	#[serde(skip)]
+	skipped_groups: Vec<UiError>,
 	/// The selected groups
 	#[serde(skip)]
-	groups: Option<Vec<String>>,
+	pub groups: Option<Vec<String>>,
 	label: String,
 	#[serde(skip)]
 	pub output: String,
 	/// The page with command data incoded
 	pub page: Page,
-	/// The last opened hook script
+	/// Open hook scripts
 	#[serde(skip)]
-	pub code: String,
-	/// Open hook shell file
-	#[serde(skip)]
-	pub opened_hook: Option<String>,
-	#[serde(skip)]
-	pub dropped_files: Vec<DroppedFile>,
+	pub editor: Editor,
 	/// if a new hook should be pre or post
 	new_hook_type: HookType,
+	/// Name of the group being created
+	#[serde(skip)]
+	new_group: Option<String>,
+	/// OS the new group is for
+	#[serde(skip)]
+	new_group_os: Option<&'static str>,
 	/// Paths to files to push
 	#[serde(skip)]
 	pub push_files: Option<Vec<String>>,
+	/// Paths to files to encrypt
+	#[serde(skip)]
+	pub encrypt_files: Option<Vec<String>>,
+	/// Encrypt or decrypt command waiting for a passphrase
+	#[serde(skip)]
+	pub pending_secret: Option<Cli>,
+	/// Passphrase typed into the prompt, cleared after every use
+	#[serde(skip)]
+	pub passphrase: String,
+	/// Repeated passphrase, encrypting needs both to match
+	#[serde(skip)]
+	pub passphrase_confirm: String,
+	/// (secret, target) of files restored by the last decrypt
+	#[serde(skip)]
+	pub restored_secrets: Vec<(PathBuf, PathBuf)>,
+	/// Stow import wizard
+	#[serde(skip)]
+	pub stow: StowWizard,
+	/// Files to find the owning group of
+	#[serde(skip)]
+	pub lookup_files: Option<Vec<String>>,
+	/// Groups found for `lookup_files`
+	#[serde(skip)]
+	pub lookup: GroupLookup,
+	/// Hooks of every group
+	#[serde(skip)]
+	pub hooks: HooksView,
+	/// Stored secrets
+	#[serde(skip)]
+	pub secrets: SecretsView,
+	/// Status of the selected groups
+	#[serde(skip)]
+	pub status: StatusView,
+	/// The command running in the background
+	#[serde(skip)]
+	pub job: Option<Job>,
+	/// Output of the hooks run by set
+	#[serde(skip)]
+	pub hook_log: HookLog,
+	/// Planned symlinks of add
+	#[serde(skip)]
+	pub add_preview: AddPreview,
+	/// Add or set waiting for conflicts to be resolved
+	#[serde(skip)]
+	pub conflicts: ConflictDialog,
+	/// Open diff between a dotfile and its target
+	#[serde(skip)]
+	pub diff: Option<DiffView>,
+	/// Files of the selected group
+	#[serde(skip)]
+	pub group_tree: GroupTree,
 }
 
 // todo add code block for hooks with the egui syntax_highlighting feature
@@ -213,16 +395,6 @@ fn fonts() -> egui::FontDefinitions {
 	font
 }
 
-fn format_vec_str(vstr: &mut Vec<String>) -> String {
-	let mut formated_str = String::with_capacity(vstr.len() * 10);
-	formated_str.push_str(&vstr.remove(0));
-	for str in vstr {
-		formated_str.push_str(", ");
-		formated_str.push_str(str);
-	}
-	formated_str
-}
-
 impl TemplateApp {
 	/// Called once before the first frame.
 	pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
@@ -246,11 +418,126 @@ impl TemplateApp {
 		// Load previous app state (if any).
 		// Note that you must enable the `persistence` feature for this to work.
 		if let Some(storage) = cc.storage {
-			return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+			let mut app: TemplateApp = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+			// start with the selection of the last used profile
+			if let Some(name) = app.profile.clone() {
+				switch_profile(&mut app, &name);
+			}
+			return app;
 		}
 
 		TemplateApp::default()
 	}
+
+	/// Run the command of `page` on `groups`
+	pub fn run_page(&mut self, ctx: &egui::Context, page: Page, groups: Vec<String>) {
+		match page.into_cli(groups) {
+			// secrets need a passphrase first
+			Ok(cli @ (Cli::Encrypt { .. } | Cli::Decrypt { .. })) => self.pending_secret = Some(cli),
+			// the status table replaces tuckr's text output
+			Ok(Cli::Status { .. }) => self.status.reload(),
+			Ok(cli) => self.start(ctx, cli),
+			Err(h) => {
+				self.output = h;
+				self.label = "select a group".into();
+			}
+		};
+	}
+
+	/// Run `cli`, asking what to do with any targets in its way first
+	pub fn start(&mut self, ctx: &egui::Context, cli: Cli) {
+		let (tx, rx) = mpsc::channel();
+		let repaint = ctx.clone();
+		let job = Job::spawn(ctx, "Checking for conflicts", move || {
+			let conflicts = find_conflicts(&cli);
+			let _ = tx.send((cli, conflicts));
+			(String::new(), ExitCode::SUCCESS)
+		});
+		self.job = Some(job.then(move |app, _| {
+			let Ok((cli, conflicts)) = rx.try_recv() else {
+				return;
+			};
+			if conflicts.is_empty() {
+				app.execute(&repaint, cli, conflicts);
+			} else {
+				app.conflicts.open(cli, conflicts);
+			}
+		}));
+	}
+
+	/// Run `cli` on a worker thread once the chosen `conflicts` are cleared
+	pub fn execute(&mut self, ctx: &egui::Context, cli: Cli, conflicts: Vec<Conflict>) {
+		let entry = HistoryEntry::new(&cli);
+		// named after the command, it might be re-run from the history
+		let command = page_of(&cli).map_or_else(|| self.page.to_string(), |(page, _)| page.to_string());
+		let job = match cli {
+			Cli::Set {
+				groups,
+				exclude,
+				force,
+				adopt,
+			} => {
+				let log = self.hook_log.start();
+				let repaint = ctx.clone();
+				let cancel = Cancel::default();
+				let (tx, rx) = mpsc::channel();
+				Job::spawn(ctx, "Set", {
+					let cancel = cancel.clone();
+					move || {
+						let mut snapshot = Snapshot::of(&groups, &exclude);
+						if let Some(snapshot) = &mut snapshot {
+							snapshot.keep_replaced(&groups, &exclude, force, adopt);
+						}
+						let mut output = resolve(&conflicts, snapshot.as_mut());
+						let (out, code) = run_set(groups, exclude, force, adopt, log, repaint, &cancel);
+						if let Some(snapshot) = snapshot {
+							let _ = tx.send(snapshot.finish(command));
+						}
+						output.push_str(&out);
+						(output, code)
+					}
+				})
+				.cancellable(cancel)
+				.then(move |app, _| {
+					if let Ok(operation) = rx.try_recv() {
+						journal(app, operation);
+					}
+				})
+			}
+			cli => {
+				let (tx, rx) = mpsc::channel();
+				Job::spawn(ctx, command.clone(), move || {
+					let mut snapshot = Snapshot::take(&cli);
+					// popped groups are kept in the archive, undo restores them from there
+					let mut output = match &cli {
+						Cli::Pop { groups } => match archive_groups(groups) {
+							Ok((archived, archive)) => {
+								if let Some(snapshot) = &mut snapshot {
+									snapshot.groups_copy(archive);
+								}
+								archived
+							}
+							Err(e) => return (format!("{e}, nothing was popped\n{}", e.fix()), ExitCode::FAILURE),
+						},
+						_ => String::new(),
+					};
+					output.push_str(&resolve(&conflicts, snapshot.as_mut()));
+					let (out, code) = run(cli);
+					if let Some(snapshot) = snapshot {
+						let _ = tx.send(snapshot.finish(command));
+					}
+					output.push_str(&out);
+					(output, code)
+				})
+				.then(move |app, _| {
+					if let Ok(operation) = rx.try_recv() {
+						journal(app, operation);
+					}
+				})
+			}
+		};
+		self.job = Some(job.record(entry));
+	}
 }
 
 impl Default for TemplateApp {
@@ -258,18 +545,47 @@ impl Default for TemplateApp {
 		Self {
 			force: false,
 			adopt: false,
-			check_count: 9975,
+			reload_groups: true,
+			group_loader: Loader::default(),
+			watcher: None,
 			page: Page::default(),
 			groups: None,
 			exclude: None,
+			archive: ArchiveView::default(),
+			archive_delete: None,
+			journal: Vec::new(),
+			undo_preview: false,
+			history: Vec::new(),
+			profiles: Vec::new(),
+			profile: None,
+			profile_name: String::new(),
 			found_groups: None,
+			groups_error: None,
+			skipped_groups: Vec::new(),
 			label: String::new(),
 			output: String::new(),
-			code: String::new(),
-			opened_hook: None,
-			dropped_files: Vec::new(),
+			editor: Editor::default(),
 			new_hook_type: HookType::default(),
+			new_group: None,
+			new_group_os: None,
 			push_files: None,
+			encrypt_files: None,
+			pending_secret: None,
+			passphrase: String::new(),
+			passphrase_confirm: String::new(),
+			restored_secrets: Vec::new(),
+			stow: StowWizard::default(),
+			lookup_files: None,
+			lookup: GroupLookup::default(),
+			hooks: HooksView::default(),
+			secrets: SecretsView::default(),
+			status: StatusView::default(),
+			job: None,
+			hook_log: HookLog::default(),
+			add_preview: AddPreview::default(),
+			conflicts: ConflictDialog::default(),
+			diff: None,
+			group_tree: GroupTree::default(),
 		}
 	}
 }
@@ -289,17 +605,62 @@ impl eframe::App for TemplateApp {
 	fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
 		// Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
 		// For inspiration and more examples, go to https://emilk.github.io/egui
-		type GroupsHandle = JoinHandle<(Result<Vec<String>, ReturnCode>, String)>;
-
-		let mut groups_handle: Option<GroupsHandle> = None;
-		if self.check_count >= 10000 {
-			groups_handle = Some(thread::spawn(|| {
-				let mut output = "".to_string();
-				(crate::groups::load_groups(&mut output), output)
-			}));
-			self.check_count = 0;
+
+		// only reload the parts of the dotfiles that changed
+		for changed in self.watcher.iter().flat_map(DotfilesWatcher::changes) {
+			match changed {
+				Changed::Configs => {
+					self.reload_groups = true;
+					self.status.reload();
+					self.add_preview.reload();
+					self.group_tree.reload();
+				}
+				Changed::Hooks => self.hooks.reload(),
+				Changed::Secrets => self.secrets.reload(),
+			}
+		}
+
+		// the file lists aren't saved, so a page restored from storage mustn't keep its old files
+		match &mut self.page {
+			Page::Push(files) => files.clone_from(&self.push_files),
+			Page::Encrypt(files) => files.clone_from(&self.encrypt_files),
+			Page::GroupIs(files) => files.clone_from(&self.lookup_files),
+			_ => (),
+		}
+
+		if self.reload_groups {
+			if self.watcher.is_none() {
+				self.watcher = DotfilesWatcher::new(ctx);
+			}
+			self.group_loader.load(ctx, find_groups);
+			self.reload_groups = false;
 		}
-		self.check_count += 1;
+
+		if let Some(found) = self.group_loader.poll() {
+			// groups for other OSes start out excluded when they first show up
+			if let Ok((found, _)) = &found {
+				let known = self.found_groups.take().unwrap_or_default();
+				let new: Vec<_> = found.iter().filter(|g| !known.contains(g)).cloned().collect();
+				let exclude = self.exclude.get_or_insert_with(Vec::new);
+				for group in default_exclude(&new) {
+					if !exclude.contains(&group) {
+						exclude.push(group);
+					}
+				}
+			}
+			(self.found_groups, self.groups_error, self.skipped_groups) = match found {
+				Ok((found, skipped)) => (Some(found), None, skipped),
+				Err(e) => (None, Some(e), Vec::new()),
+			};
+			// keep the selection across reloads, dropping groups that are gone
+			self.groups = match (self.groups.take(), &self.found_groups) {
+				(Some(selected), Some(found)) => Some(selected.into_iter().filter(|g| found.contains(g)).collect()),
+				(_, found) => found.clone(),
+			};
+			self.hooks.reload();
+		}
+
+		poll_job(self);
 
 		egui::CentralPanel::default()
 			.frame(
@@ -314,7 +675,14 @@ impl eframe::App for TemplateApp {
 				ui.style_mut().spacing.item_spacing = egui::vec2(15.0, 15.0);
 				ui.separator();
 
+				job_progress(self, ui);
+
 				ui.vertical_centered(|ui| {
+					// nothing else can run until the job is done
+					if self.job.is_some() {
+						ui.disable();
+					}
+
 					ui.horizontal(|ui| {
 						egui::ComboBox::from_id_source(4)
 							.selected_text(format!("{}", self.page))
@@ -337,6 +705,22 @@ impl eframe::App for TemplateApp {
 								);
 								ui.selectable_value(&mut self.page, Page::Status, "Status");
 								ui.selectable_value(&mut self.page, Page::Hooks, "Hooks");
+								ui.selectable_value(
+									&mut self.page,
+									Page::Encrypt(self.encrypt_files.clone()),
+									"Encrypt",
+								);
+								ui.selectable_value(&mut self.page, Page::Decrypt(self.exclude.clone()), "Decrypt");
+								ui.selectable_value(&mut self.page, Page::FromStow, "From Stow");
+								ui.selectable_value(
+									&mut self.page,
+									Page::GroupIs(self.lookup_files.clone()),
+									"Group is",
+								);
+								ui.selectable_value(&mut self.page, Page::Secrets, "Secrets");
+								ui.selectable_value(&mut self.page, Page::Files, "Files");
+								ui.selectable_value(&mut self.page, Page::History, "History");
+								ui.selectable_value(&mut self.page, Page::Archive, "Archive");
 								ui.style_mut().spacing.item_spacing = egui::vec2(15.0, 15.0);
 							});
 
@@ -356,19 +740,23 @@ impl eframe::App for TemplateApp {
 							)
 						}) || ui.add(Button::image(refresh_icon)).clicked()
 						{
-							self.check_count = 0;
-							groups_handle = Some(thread::spawn(|| {
-								let mut output = "".to_string();
-								(crate::groups::load_groups(&mut output), output)
-							}));
+							// watch again in case the dotfiles directory moved or was created
+							self.watcher = None;
+							self.reload_groups = true;
+							self.hooks.reload();
+							self.secrets.reload();
+							self.status.reload();
+							self.group_tree.reload();
+							ui.ctx().request_repaint();
 						}
+
+						undo_button(self, ui);
 					});
 
 					ui.end_row();
 					// group  selector
-					if self.found_groups.is_some() {
-						group_select(self, ui);
-					}
+					group_select(self, ui);
+					profile_bar(self, ui);
 
 					// flags
 					ui.horizontal(|ui| {
@@ -382,6 +770,14 @@ impl eframe::App for TemplateApp {
 
 						ui.style_mut().spacing.item_spacing = egui::vec2(15.0, 15.0);
 					});
+					// keep the flags of the page in sync with the checkboxes
+					if let Page::Add(_, force, adopt) | Page::Set(_, force, adopt) = &mut self.page {
+						*force = self.force;
+						*adopt = self.adopt;
+					}
+					if let Page::Add(exclude, _, _) | Page::Set(exclude, _, _) | Page::Rm(exclude) = &mut self.page {
+						exclude.clone_from(&self.exclude);
+					}
 
 					// if the page is hooks list groups and hook files then open it in a editer
 					match self.page {
@@ -390,21 +786,15 @@ impl eframe::App for TemplateApp {
 							let new_icon = Image::new(include_image!("../assets/new.svg")).fit_to_original_size(0.23);
 							ui.horizontal(|ui| {
 								if (ui.add(Button::image(save_icon))).clicked() {
-									let save = fs::write(
-										match &self.opened_hook {
-											Some(p) => p,
-											None => return,
-										},
-										&self.code,
-									);
-									match save {
-										Ok(()) => self.output = "saved".to_string(),
-										Err(e) => self.output = e.to_string(),
+									match self.editor.active_mut().map(|tab| tab.save()) {
+										Some(Ok(())) => self.output = "saved".to_string(),
+										Some(Err(e)) => self.output = e.to_string(),
+										None => return,
 									}
 								}
 								ui.add_space(3.0);
 
-								let hooks_dir = match tuckr::dotfiles::get_dotfiles_path(&mut "".into()) {
+								let hooks_dir = match dotfiles_dir() {
 									Ok(p) => Some(p.join("Hooks")),
 									Err(e) => return self.output.push_str(&e.to_string()),
 								};
@@ -413,86 +803,188 @@ impl eframe::App for TemplateApp {
 								new_hook(self, ui, hooks_dir, new_icon);
 							});
 
+							if let Ok(dotfiles) = dotfiles_dir() {
+								hooks_browser(self, ui, &dotfiles.join("Hooks"));
+							}
 							code_editer(self, ui);
 						}
 						Page::Push(_) => push_file_picker(self, ui),
+						Page::Encrypt(_) => encrypt_file_picker(self, ui),
+						Page::Decrypt(_) => {
+							if self.found_groups.is_some() {
+								exclude_select(self, ui);
+								self.page = Page::Decrypt(self.exclude.clone());
+							}
+							restored_secrets(self, ui);
+						}
+						Page::FromStow => stow_wizard(self, ui),
+						Page::GroupIs(_) => group_lookup(self, ui),
+						Page::Secrets => secrets_inventory(self, ui),
+						Page::Status => status_table(self, ui),
+						Page::Files => group_tree(self, ui),
+						Page::History => history_page(self, ui),
+						Page::Archive => archive_page(self, ui),
+						Page::Pop => {
+							ui.label("Popped groups are archived and can be restored from the Archive page");
+						}
+						Page::Rm(_) => exclude_select(self, ui),
+						Page::Set(_, _, _) => {
+							exclude_select(self, ui);
+							hook_log(self, ui);
+						}
+						Page::Add(_, force, adopt) => {
+							exclude_select(self, ui);
+							let exclude = self.exclude.clone().unwrap_or_default();
+							add_preview(self, ui, &exclude, force, adopt);
+						}
 						_ => (),
 					}
 
 					// groups
-					ui.label(format_vec_str(
-						&mut self.found_groups.clone().unwrap_or(vec!["".into()]),
-					));
-
-					if self.page != Page::Hooks && ui.button("Exacute").clicked() {
-						match self
-							.page
-							.clone()
-							.into_cli(self.groups.clone().unwrap_or(vec![r"\*".into()]))
-						{
-							Ok(cli) => self.output = run(cli).0,
-							Err(h) => {
-								self.output = h;
-								self.label = "select a group".into();
-							}
-						};
+					// an empty Configs has no groups to list yet
+					if let Some(found) = self.found_groups.as_ref().filter(|f| !f.is_empty()) {
+						ui.label(found.join(", "));
+					}
+
+					if !matches!(
+						self.page,
+						Page::Hooks | Page::FromStow | Page::Files | Page::History | Page::Archive
+					) && ui.button("Exacute").clicked()
+					{
+						let groups = self.groups.clone().unwrap_or(vec![r"\*".into()]);
+						self.run_page(ui.ctx(), self.page.clone(), groups);
 					}
 
 					ui.label(&self.output);
 
 					ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
 						egui::warn_if_debug_build(ui);
-						#[cfg(debug_assertions)]
-						ui.label(self.check_count.to_string());
 					});
 				});
 			});
 
-		if let Some(groups) = groups_handle {
-			if let Ok(g) = groups.join() {
-				self.output.push_str(&g.1);
-				self.found_groups = g.0.ok();
-				self.groups = self.found_groups.clone()
-			}
-		}
+		passphrase_prompt(self, ctx);
+		conflict_dialog(self, ctx);
+		diff_window(self, ctx);
+		undo_window(self, ctx);
+		unsaved_dialog(self, ctx);
 	}
 }
 
-fn code_editer(app: &mut TemplateApp, ui: &mut Ui) {
-	let theme = egui_extras::syntax_highlighting::CodeTheme::from_style(ui.style());
-	// todo patch egui_extras to use tmTheme file
+fn group_select(app: &mut TemplateApp, ui: &mut Ui) {
+	let loading = app.group_loader.loading();
+	if app.found_groups.is_none() {
+		app.group_loader.spinner(ui, "loading…");
+		if let Some(error) = app.groups_error.as_ref().filter(|_| !loading) {
+			error_message(ui, error);
+		}
+		return;
+	}
 
-	let mut layouter = |ui: &egui::Ui, code: &str, wrap_width: f32| {
-		let mut layout_job = egui_extras::syntax_highlighting::highlight(ui.ctx(), &theme, code, "bash");
-		layout_job.wrap.max_width = wrap_width;
-		ui.fonts(|f| f.layout_job(layout_job))
+	ui.horizontal(|ui| {
+		let groups = app.groups.get_or_insert_with(Vec::new);
+		let found_groups = app.found_groups.as_ref().expect("groups are loaded");
+		ui.add(MultiSelect::new(
+			"test_multiselect",
+			&mut groups.clone(),
+			groups,
+			found_groups,
+			group_label,
+			match app.page {
+				Page::Push(_) | Page::Encrypt(_) | Page::Files => &1,
+				_ => &255,
+			},
+			if loading {
+				"loading…"
+			} else {
+				"Choose one or more groups"
+			},
+		));
+		// the old groups stay selectable while they reload
+		if loading {
+			ui.spinner();
+		}
+		new_group(app, ui);
+	});
+	for error in &app.skipped_groups {
+		error_message(ui, error);
+	}
+}
+
+/// Inline form to create a group in `Configs/` and select it
+fn new_group(app: &mut TemplateApp, ui: &mut Ui) {
+	let Some(name) = &mut app.new_group else {
+		if ui.button("➕").on_hover_text("new group").clicked() {
+			app.new_group = Some(String::new());
+		}
+		return;
 	};
 
-	egui::ScrollArea::vertical().show(ui, |ui| {
-		ui.add(
-			egui::TextEdit::multiline(&mut app.code)
-				.font(egui::TextStyle::Monospace) // for cursor height
-				.code_editor()
-				.desired_rows(10)
-				.lock_focus(true)
-				.desired_width(f32::INFINITY)
-				.layouter(&mut layouter),
-		);
-	});
+	ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+	ui.add(
+		egui::TextEdit::singleline(name)
+			.hint_text("group name")
+			.desired_width(100.0),
+	);
+	egui::ComboBox::from_id_source("new_group_os")
+		.selected_text(app.new_group_os.unwrap_or("any OS"))
+		.show_ui(ui, |ui| {
+			ui.selectable_value(&mut app.new_group_os, None, "any OS");
+			for os in OS_SUFFIXES {
+				ui.selectable_value(&mut app.new_group_os, Some(os), os);
+			}
+		});
+
+	let found_groups = app.found_groups.as_deref().unwrap_or_default();
+	let group = new_group_name(name.trim(), app.new_group_os, found_groups);
+	let create = ui.add_enabled(group.is_ok(), Button::new("Create")).clicked();
+	let cancel = ui.small_button("🗙").on_hover_text("cancel").clicked();
+	if let (Err(problem), false) = (&group, name.is_empty()) {
+		ui.colored_label(Color32::LIGHT_RED, problem);
+	}
+
+	if let (true, Ok(group)) = (create, group) {
+		match create_group(&group) {
+			Ok(()) => {
+				// select it right away, the watcher picks it up for the next reload
+				app.found_groups.get_or_insert_with(Vec::new).push(group.clone());
+				app.groups = Some(vec![group]);
+				app.new_group = None;
+			}
+			Err(e) => app.output = format!("{e}\n{}", e.fix()),
+		}
+	} else if cancel {
+		app.new_group = None;
+	}
 }
 
-fn group_select(app: &mut TemplateApp, ui: &mut Ui) {
+/// OS variants are shown under their base group, greyed out if they're for another OS
+fn group_label(ui: &mut Ui, group: &str) -> egui::Response {
+	let text = match group_os(group) {
+		Some(_) => RichText::new(format!("  ↳ {group}")),
+		None => RichText::new(group),
+	};
+	match group_os(group).filter(|_| !is_valid_target(group)) {
+		Some(os) => ui
+			.selectable_label(false, text.weak())
+			.on_hover_text(format!("only for {os}")),
+		None => ui.selectable_label(false, text),
+	}
+}
+
+fn exclude_select(app: &mut TemplateApp, ui: &mut Ui) {
+	let Some(found_groups) = &app.found_groups else {
+		return;
+	};
+	let exclude = app.exclude.get_or_insert_with(Vec::new);
 	ui.add(MultiSelect::new(
-		"test_multiselect",
-		&mut app.groups.as_mut().unwrap().clone(),
-		app.groups.as_mut().unwrap(),
-		app.found_groups.as_ref().unwrap(),
-		|ui, _text| ui.selectable_label(false, _text),
-		match app.page {
-			Page::Push(_) => &1,
-			_ => &255,
-		},
-		"Choose one or more groups",
+		"exclude_multiselect",
+		&mut exclude.clone(),
+		exclude,
+		found_groups,
+		group_label,
+		&255,
+		"Choose groups to exclude",
 	));
 }
 
@@ -501,11 +993,19 @@ fn new_hook(app: &mut TemplateApp, ui: &mut Ui, hooks_dir: Option<PathBuf>, new_
 		let mut file_name = app.new_hook_type.to_string().to_lowercase();
 		file_name.push_str(".sh");
 
-		rfd::FileDialog::new()
+		let hook = rfd::FileDialog::new()
 			.add_filter("shell scripts", &["sh"])
 			.set_file_name(file_name)
 			.set_directory(hooks_dir.unwrap_or_default())
 			.save_file();
+		if let Some(hook) = hook {
+			match create_hook(&hook) {
+				Ok(()) => open_hook(app, &hook),
+				Err(e) => app.output = format!("couldn't create {}: {e}", hook.display()),
+			}
+			// list the new hook
+			app.hooks.reload();
+		}
 	}
 	ui.add_space(3.0);
 
@@ -0,0 +1,205 @@
+use crate::app::TemplateApp;
+use crate::error::UiError;
+use crate::format::format_time;
+use crate::groups::{configs_dir, copy_path, home_dir, load_groups, walk_files};
+use crate::loader::Loader;
+use crate::worker::Job;
+use egui::Ui;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A group that was popped, kept in `<archive>/<secs>/<group>`
+pub struct ArchivedGroup {
+	pub group: String,
+	pub path: PathBuf,
+	pub popped: SystemTime,
+	pub files: usize,
+}
+
+/// Popped groups, read in the background when the archive page lists them
+#[derive(Default)]
+pub struct ArchiveView {
+	/// None until the first read came back
+	archived: Option<Vec<ArchivedGroup>>,
+	/// the archive was asked for since it last changed
+	requested: bool,
+	loader: Loader<Vec<ArchivedGroup>>,
+}
+
+impl ArchiveView {
+	/// Read the archive again on the next frame
+	pub fn reload(&mut self) {
+		self.requested = false;
+	}
+}
+
+/// Where popped groups are kept, outside the dotfiles so they aren't committed
+fn archive_dir() -> Option<PathBuf> {
+	let data = std::env::var_os("XDG_DATA_HOME")
+		.or_else(|| std::env::var_os("APPDATA"))
+		.map(PathBuf::from)
+		.or_else(|| home_dir().map(|h| h.join(".local").join("share")))?;
+	Some(data.join("tuckr-ui").join("archive"))
+}
+
+/// Copy the groups into the archive before pop deletes them, `\*` is all groups.
+/// Returns what was done and the archive directory of this pop
+pub fn archive_groups(groups: &[String]) -> Result<(String, PathBuf), UiError> {
+	let groups = match groups.iter().any(|g| g == r"\*") {
+		true => load_groups()?,
+		false => groups.to_vec(),
+	};
+	let configs_dir = configs_dir()?;
+	let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+	let archive = archive_dir().ok_or(UiError::NoArchive)?.join(secs.to_string());
+
+	let mut output = String::new();
+	for group in groups {
+		let path = configs_dir.join(&group);
+		copy_path(&path, &archive.join(&group)).map_err(|source| UiError::Failed {
+			action: "archive",
+			path,
+			source,
+		})?;
+		output.push_str(&format!("archived {group}\n"));
+	}
+	Ok((output, archive))
+}
+
+/// Every archived group, newest first
+pub fn list_archive() -> Vec<ArchivedGroup> {
+	let Some(Ok(entries)) = archive_dir().map(fs::read_dir) else {
+		return Vec::new();
+	};
+
+	let mut archived = Vec::new();
+	for entry in entries.flatten() {
+		let Ok(secs) = entry.file_name().to_string_lossy().parse::<u64>() else {
+			continue;
+		};
+		let Ok(groups) = entry.path().read_dir() else {
+			continue;
+		};
+		for group in groups.flatten() {
+			let path = group.path();
+			archived.push(ArchivedGroup {
+				group: group.file_name().to_string_lossy().into_owned(),
+				files: walk_files(&path).len(),
+				popped: UNIX_EPOCH + Duration::from_secs(secs),
+				path,
+			});
+		}
+	}
+
+	archived.sort_by(|a, b| (b.popped, &a.group).cmp(&(a.popped, &b.group)));
+	archived
+}
+
+/// Move an archived group back into `Configs/`
+fn restore(group: &str, path: &Path) -> (String, ExitCode) {
+	let configs_dir = match configs_dir() {
+		Ok(p) => p,
+		Err(e) => return (e.to_string(), ExitCode::FAILURE),
+	};
+	let group_dir = configs_dir.join(group);
+	if group_dir.exists() {
+		return (format!("{group} already exists"), ExitCode::FAILURE);
+	}
+
+	match copy_path(path, &group_dir).and_then(|()| fs::remove_dir_all(path)) {
+		Ok(()) => {
+			// remove the timestamp directory once its last group was restored
+			if let Some(parent) = path.parent() {
+				let _ = fs::remove_dir(parent);
+			}
+			(format!("restored {group}"), ExitCode::SUCCESS)
+		}
+		Err(e) => (format!("couldn't restore {group}: {e}"), ExitCode::FAILURE),
+	}
+}
+
+/// Remove an archived group for good
+fn delete(group: &str, path: &Path) -> (String, ExitCode) {
+	match fs::remove_dir_all(path) {
+		Ok(()) => {
+			if let Some(parent) = path.parent() {
+				let _ = fs::remove_dir(parent);
+			}
+			(format!("deleted {group}"), ExitCode::SUCCESS)
+		}
+		Err(e) => (format!("couldn't delete {group}: {e}"), ExitCode::FAILURE),
+	}
+}
+
+/// Popped groups with buttons to restore or delete them for good
+pub fn archive_page(app: &mut TemplateApp, ui: &mut Ui) {
+	let view = &mut app.archive;
+	if !view.requested {
+		view.loader.load(ui.ctx(), list_archive);
+		view.requested = true;
+	}
+	if let Some(archived) = view.loader.poll() {
+		view.archived = Some(archived);
+	}
+	view.loader.spinner(ui, "loading…");
+	let Some(archived) = &view.archived else {
+		return;
+	};
+	if archived.is_empty() {
+		ui.label("No popped groups");
+		return;
+	}
+
+	let mut restoring = None;
+	egui::ScrollArea::vertical()
+		.id_source("archive")
+		.max_height(300.0)
+		.show(ui, |ui| {
+			egui::Grid::new("archive_grid")
+				.striped(true)
+				.num_columns(4)
+				.show(ui, |ui| {
+					ui.strong("Group");
+					ui.strong("Popped");
+					ui.strong("Files");
+					ui.end_row();
+
+					for archived in archived.iter() {
+						ui.label(&archived.group)
+							.on_hover_text(archived.path.display().to_string());
+						ui.label(format_time(archived.popped));
+						ui.label(archived.files.to_string());
+						ui.horizontal(|ui| {
+							if ui.button("Restore").clicked() {
+								restoring = Some((archived.group.clone(), archived.path.clone()));
+							}
+							if ui.button("🗑").on_hover_text("delete for good").clicked() {
+								app.archive_delete = Some((archived.group.clone(), archived.path.clone()));
+							}
+						});
+						ui.end_row();
+					}
+				});
+		});
+
+	if let Some((group, path)) = app.archive_delete.clone() {
+		let (mut confirm, mut cancel) = (false, false);
+		ui.horizontal(|ui| {
+			ui.label(format!("Delete {group} for good? It can't be restored afterwards"));
+			confirm = ui.button("Delete").clicked();
+			cancel = ui.button("Cancel").clicked();
+		});
+		if confirm {
+			app.job = Some(Job::spawn(ui.ctx(), "Delete", move || delete(&group, &path)));
+		}
+		if confirm || cancel {
+			app.archive_delete = None;
+		}
+	}
+
+	if let Some((group, path)) = restoring {
+		app.job = Some(Job::spawn(ui.ctx(), "Restore", move || restore(&group, &path)));
+	}
+}
@@ -1,5 +1,6 @@
 #![allow(unused)]
 use crate::app::Page;
+use std::process::ExitCode;
 use tuckr::Cli;
 use tuckr::{fileops, hooks, secrets, symlinks};
 
@@ -21,8 +22,12 @@ pub use fileops::push_cmd;
 pub use hooks::set_cmd;
 /// Decrypt command
 pub use secrets::decrypt_cmd;
+/// Decrypt command with the passphrase given instead of prompted for, also returns (secret, target) of every restored file
+pub use secrets::decrypt_cmd_with_password;
 /// Encrypt command
 pub use secrets::encrypt_cmd;
+/// Encrypt command with the passphrase given instead of prompted for
+pub use secrets::encrypt_cmd_with_password;
 /// Add command
 pub use symlinks::add_cmd;
 /// Rm command
@@ -30,7 +35,89 @@ pub use symlinks::remove_cmd;
 /// Starus command
 pub use symlinks::status_cmd;
 
-pub fn run(cli: Cli) -> (String, std::process::ExitCode) {
+/// tuckr's help
+pub const HELP: &str = include_str!("../assets/help.txt");
+
+/// The tuckr subcommand `cli` is run with
+fn subcommand(cli: &Cli) -> &'static str {
+	match cli {
+		Cli::Set { .. } => "set",
+		Cli::Add { .. } => "add",
+		Cli::Rm { .. } => "rm",
+		Cli::Status { .. } => "status",
+		Cli::Encrypt { .. } => "encrypt",
+		Cli::Decrypt { .. } => "decrypt",
+		Cli::FromStow => "from-stow",
+		Cli::Init => "init",
+		Cli::LsHooks => "ls-hooks",
+		Cli::LsSecrets => "ls-secrets",
+		Cli::Push { .. } => "push",
+		Cli::Pop { .. } => "pop",
+		Cli::GroupIs { .. } => "groupis",
+	}
+}
+
+/// Arguments of the tuckr command line that does the same as `cli`
+pub fn args(cli: &Cli) -> Vec<String> {
+	let mut args = vec![subcommand(cli).to_string()];
+	let flags = |args: &mut Vec<String>, exclude: &[String], force: bool, adopt: bool| {
+		if !exclude.is_empty() {
+			args.push("--exclude".into());
+			args.push(exclude.join(","));
+		}
+		if force {
+			args.push("--force".into());
+		}
+		if adopt {
+			args.push("--adopt".into());
+		}
+	};
+
+	match cli {
+		Cli::Add {
+			groups,
+			exclude,
+			force,
+			adopt,
+		}
+		| Cli::Set {
+			groups,
+			exclude,
+			force,
+			adopt,
+		} => {
+			args.extend_from_slice(groups);
+			flags(&mut args, exclude, *force, *adopt);
+		}
+		Cli::Rm { groups, exclude } | Cli::Decrypt { groups, exclude } => {
+			args.extend_from_slice(groups);
+			flags(&mut args, exclude, false, false);
+		}
+		Cli::Push { group, files: paths } | Cli::Encrypt { group, dotfiles: paths } => {
+			args.push(group.clone());
+			args.extend_from_slice(paths);
+		}
+		Cli::Pop { groups } => args.extend_from_slice(groups),
+		Cli::Status { groups } => args.extend_from_slice(groups.as_deref().unwrap_or_default()),
+		Cli::GroupIs { files } => args.extend_from_slice(files),
+		Cli::Init | Cli::FromStow | Cli::LsHooks | Cli::LsSecrets => (),
+	}
+	args
+}
+
+/// Run encrypt or decrypt with the passphrase from the ui prompt, tuckr would ask for it on a terminal
+pub fn run_with_passphrase(cli: Cli, passphrase: &str) -> (String, ExitCode) {
+	match cli {
+		Cli::Encrypt { group, dotfiles } => secrets::encrypt_cmd_with_password(&group, &dotfiles, passphrase),
+		Cli::Decrypt { groups, exclude } => {
+			let (output, code, _) = secrets::decrypt_cmd_with_password(&groups, &exclude, passphrase);
+			(output, code)
+		}
+		cli => run(cli),
+	}
+}
+
+pub fn run(cli: Cli) -> (String, ExitCode) {
 	match cli {
 		Cli::Set {
 			groups,
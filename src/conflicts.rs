@@ -0,0 +1,276 @@
+use crate::app::TemplateApp;
+use crate::diff::DiffView;
+use crate::groups::move_path;
+use crate::journal::{Change, Snapshot};
+use crate::plan::{plan_add, Action};
+use egui::{Align2, Button, Ui};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tuckr::Cli;
+
+/// How to deal with a target that's already there
+#[derive(Clone, Copy, PartialEq)]
+pub enum Choice {
+	Overwrite,
+	/// move the target into the repo in place of the dotfile
+	Adopt,
+	Skip,
+	/// rename the target to `<name>.bak`, then link it
+	Backup,
+}
+
+impl Choice {
+	const ALL: [Choice; 4] = [Choice::Overwrite, Choice::Adopt, Choice::Skip, Choice::Backup];
+
+	fn name(self) -> &'static str {
+		match self {
+			Choice::Overwrite => "Overwrite",
+			Choice::Adopt => "Adopt",
+			Choice::Skip => "Skip",
+			Choice::Backup => "Back up",
+		}
+	}
+}
+
+pub struct Conflict {
+	pub group: String,
+	/// dotfile in the repo
+	pub path: PathBuf,
+	/// the file in the way
+	pub target: PathBuf,
+	/// the target is a real directory, overwriting removes everything in it
+	pub is_dir: bool,
+	pub choice: Choice,
+}
+
+/// Targets that are in the way of add or set, none if force or adopt decide for every file
+pub fn find_conflicts(cli: &Cli) -> Vec<Conflict> {
+	match cli {
+		Cli::Add {
+			groups,
+			exclude,
+			force: false,
+			adopt: false,
+		}
+		| Cli::Set {
+			groups,
+			exclude,
+			force: false,
+			adopt: false,
+		} => plan_add(groups, exclude, false, false)
+			.into_iter()
+			.filter(|p| p.action == Action::Conflict)
+			.map(|p| Conflict {
+				group: p.group,
+				path: p.path,
+				is_dir: p.target.symlink_metadata().is_ok_and(|m| m.is_dir()),
+				target: p.target,
+				choice: Choice::Skip,
+			})
+			.collect(),
+		_ => Vec::new(),
+	}
+}
+
+fn remove(path: &Path) -> io::Result<()> {
+	match path.symlink_metadata()?.is_dir() {
+		true => fs::remove_dir_all(path),
+		false => fs::remove_file(path),
+	}
+}
+
+/// `<target>.bak`, or with a timestamp if that's taken too
+fn backup_path(target: &Path) -> PathBuf {
+	let mut backup = target.as_os_str().to_owned();
+	backup.push(".bak");
+	let backup = PathBuf::from(backup);
+	if backup.symlink_metadata().is_err() {
+		return backup;
+	}
+
+	let secs = std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map_or(0, |d| d.as_secs());
+	let mut stamped = backup.into_os_string();
+	stamped.push(format!(".{secs}"));
+	stamped.into()
+}
+
+/// Remove `path`, keeping a copy in the snapshot so it can be restored
+fn remove_kept(path: &Path, snapshot: Option<&mut Snapshot>) -> io::Result<()> {
+	let Some(snapshot) = snapshot else {
+		return remove(path);
+	};
+	let backup = snapshot.keep(path)?;
+	remove(path)?;
+	snapshot.record(Change::Deleted {
+		path: path.to_path_buf(),
+		backup,
+	});
+	Ok(())
+}
+
+/// Move `from` to `to`, replacing what's there. The replaced file is kept in the snapshot
+fn move_kept(from: &Path, to: &Path, mut snapshot: Option<&mut Snapshot>) -> io::Result<()> {
+	let backup = match (snapshot.as_deref_mut(), to.symlink_metadata().is_ok()) {
+		(Some(snapshot), true) => Some(snapshot.keep(to)?),
+		_ => None,
+	};
+	move_path(from, to)?;
+	if let Some(snapshot) = snapshot {
+		if let Some(backup) = backup {
+			snapshot.record(Change::Deleted {
+				path: to.to_path_buf(),
+				backup,
+			});
+		}
+		snapshot.record(Change::Moved {
+			from: from.to_path_buf(),
+			to: to.to_path_buf(),
+		});
+	}
+	Ok(())
+}
+
+/// Clear the chosen targets out of the way so they get linked, returns what was done.
+/// Every change is recorded in `snapshot` so undo can reverse it
+pub fn resolve(conflicts: &[Conflict], mut snapshot: Option<&mut Snapshot>) -> String {
+	let mut output = String::new();
+	for conflict in conflicts {
+		let target = conflict.target.display();
+		let done = match conflict.choice {
+			Choice::Skip => continue,
+			Choice::Overwrite => {
+				remove_kept(&conflict.target, snapshot.as_deref_mut()).map(|()| format!("removed {target}"))
+			}
+			// files are replaced by the move, so the dotfile stays if it fails
+			Choice::Adopt => move_kept(&conflict.target, &conflict.path, snapshot.as_deref_mut())
+				.map(|()| format!("adopted {target} into {}", conflict.group)),
+			Choice::Backup => {
+				let backup = backup_path(&conflict.target);
+				move_kept(&conflict.target, &backup, snapshot.as_deref_mut())
+					.map(|()| format!("backed up {target} to {}", backup.display()))
+			}
+		};
+
+		match done {
+			Ok(line) => output.push_str(&line),
+			Err(e) => output.push_str(&format!(
+				"couldn't {} {target}: {e}",
+				conflict.choice.name().to_lowercase()
+			)),
+		}
+		output.push('\n');
+	}
+	output
+}
+
+/// Add or set waiting for conflicts to be resolved
+#[derive(Default)]
+pub struct ConflictDialog {
+	cli: Option<Cli>,
+	conflicts: Vec<Conflict>,
+	/// the user agreed to remove the directories chosen for overwrite
+	remove_dirs: bool,
+}
+
+impl ConflictDialog {
+	pub fn open(&mut self, cli: Cli, conflicts: Vec<Conflict>) {
+		self.cli = Some(cli);
+		self.conflicts = conflicts;
+		self.remove_dirs = false;
+	}
+}
+
+fn choice_select(ui: &mut Ui, id: impl std::hash::Hash, choice: &mut Choice) {
+	egui::ComboBox::from_id_source(id)
+		.selected_text(choice.name())
+		.show_ui(ui, |ui| {
+			ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+			for c in Choice::ALL {
+				ui.selectable_value(choice, c, c.name());
+			}
+		});
+}
+
+/// Choose what to do with every conflicting target, then run the command
+pub fn conflict_dialog(app: &mut TemplateApp, ctx: &egui::Context) {
+	let dialog = &mut app.conflicts;
+	if dialog.cli.is_none() {
+		return;
+	}
+
+	let mut open = true;
+	let mut apply = false;
+	let mut diff = None;
+	egui::Window::new("Conflicts")
+		.collapsible(false)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.open(&mut open)
+		.show(ctx, |ui| {
+			ui.style_mut().spacing.item_spacing = egui::vec2(10.0, 10.0);
+			ui.label(format!("{} targets already exist", dialog.conflicts.len()));
+
+			ui.horizontal(|ui| {
+				ui.label("All:");
+				for c in Choice::ALL {
+					if ui.button(c.name()).clicked() {
+						dialog.conflicts.iter_mut().for_each(|conflict| conflict.choice = c);
+					}
+				}
+			});
+
+			egui::ScrollArea::vertical()
+				.id_source("conflicts")
+				.max_height(250.0)
+				.show(ui, |ui| {
+					egui::Grid::new("conflicts_grid")
+						.striped(true)
+						.num_columns(4)
+						.show(ui, |ui| {
+							for (i, conflict) in dialog.conflicts.iter_mut().enumerate() {
+								choice_select(ui, ("conflict", i), &mut conflict.choice);
+								ui.label(&conflict.group);
+								let target = match conflict.is_dir {
+									true => format!("📁 {}", conflict.target.display()),
+									false => conflict.target.display().to_string(),
+								};
+								ui.label(target).on_hover_text(conflict.path.display().to_string());
+								if conflict.target.is_file() && ui.small_button("diff").clicked() {
+									diff = Some(DiffView::open(ui.ctx(), &conflict.path, &conflict.target));
+								}
+								ui.end_row();
+							}
+						});
+				});
+
+			let dirs = dialog
+				.conflicts
+				.iter()
+				.filter(|c| c.is_dir && c.choice == Choice::Overwrite)
+				.count();
+			if dirs > 0 {
+				ui.checkbox(
+					&mut dialog.remove_dirs,
+					format!("remove {dirs} directories with everything in them"),
+				);
+			}
+			apply = ui
+				.add_enabled(dirs == 0 || dialog.remove_dirs, Button::new("Apply"))
+				.clicked();
+		});
+
+	if !open {
+		*dialog = ConflictDialog::default();
+	}
+	if diff.is_some() {
+		app.diff = diff;
+	}
+	if apply {
+		let dialog = std::mem::take(&mut app.conflicts);
+		if let Some(cli) = dialog.cli {
+			app.execute(ctx, cli, dialog.conflicts);
+		}
+	}
+}
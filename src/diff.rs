@@ -0,0 +1,283 @@
+use crate::app::TemplateApp;
+use crate::loader::Loader;
+use egui::text::LayoutJob;
+use egui::{Color32, Ui};
+use egui_extras::syntax_highlighting::{highlight, CodeTheme};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Diffs bigger than this many line pairs are shown as fully changed
+const MAX_DIFF: usize = 4_000_000;
+
+const REMOVED: Color32 = Color32::from_rgba_premultiplied(90, 20, 20, 90);
+const ADDED: Color32 = Color32::from_rgba_premultiplied(20, 80, 30, 90);
+
+/// A line of each side, None where the other side has a line that isn't in this one
+struct Row {
+	left: Option<(usize, String)>,
+	right: Option<(usize, String)>,
+}
+
+impl Row {
+	fn changed(&self) -> bool {
+		match (&self.left, &self.right) {
+			(Some((_, l)), Some((_, r))) => l != r,
+			_ => true,
+		}
+	}
+}
+
+enum Op {
+	Same(usize, usize),
+	Removed(usize),
+	Added(usize),
+}
+
+/// Line diff from the longest common subsequence of `left` and `right`
+fn diff_ops(left: &[&str], right: &[&str]) -> Vec<Op> {
+	let (n, m) = (left.len(), right.len());
+	if n.saturating_mul(m) > MAX_DIFF {
+		return (0..n).map(Op::Removed).chain((0..m).map(Op::Added)).collect();
+	}
+
+	// lcs[i][j] is the length of the lcs of left[i..] and right[j..]
+	let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+	for i in (0..n).rev() {
+		for j in (0..m).rev() {
+			lcs[i][j] = match left[i] == right[j] {
+				true => lcs[i + 1][j + 1] + 1,
+				false => lcs[i + 1][j].max(lcs[i][j + 1]),
+			};
+		}
+	}
+
+	let mut ops = Vec::with_capacity(n.max(m));
+	let (mut i, mut j) = (0, 0);
+	while i < n && j < m {
+		if left[i] == right[j] {
+			ops.push(Op::Same(i, j));
+			(i, j) = (i + 1, j + 1);
+		} else if lcs[i + 1][j] >= lcs[i][j + 1] {
+			ops.push(Op::Removed(i));
+			i += 1;
+		} else {
+			ops.push(Op::Added(j));
+			j += 1;
+		}
+	}
+	ops.extend((i..n).map(Op::Removed));
+	ops.extend((j..m).map(Op::Added));
+	ops
+}
+
+/// Side by side rows, removed lines are paired with the lines added in their place
+fn diff_rows(left: &str, right: &str) -> Vec<Row> {
+	let left: Vec<_> = left.lines().collect();
+	let right: Vec<_> = right.lines().collect();
+	let line = |lines: &[&str], i: usize| Some((i + 1, lines[i].to_string()));
+
+	let mut rows = Vec::new();
+	let mut removed = Vec::new();
+	let mut added = Vec::new();
+	let flush = |removed: &mut Vec<usize>, added: &mut Vec<usize>, rows: &mut Vec<Row>| {
+		for k in 0..removed.len().max(added.len()) {
+			rows.push(Row {
+				left: removed.get(k).and_then(|&i| line(&left, i)),
+				right: added.get(k).and_then(|&j| line(&right, j)),
+			});
+		}
+		removed.clear();
+		added.clear();
+	};
+
+	for op in diff_ops(&left, &right) {
+		match op {
+			Op::Removed(i) => removed.push(i),
+			Op::Added(j) => added.push(j),
+			Op::Same(i, j) => {
+				flush(&mut removed, &mut added, &mut rows);
+				rows.push(Row {
+					left: line(&left, i),
+					right: line(&right, j),
+				});
+			}
+		}
+	}
+	flush(&mut removed, &mut added, &mut rows);
+	rows
+}
+
+/// Err if one of the files can't be shown as text
+type Rows = Result<Vec<Row>, String>;
+
+/// Diff between a dotfile in the repo and the file deployed in its place
+pub struct DiffView {
+	path: PathBuf,
+	target: PathBuf,
+	/// None until the files are read and compared
+	rows: Option<Rows>,
+	language: String,
+	loader: Loader<Rows>,
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+	let bytes = fs::read(path).map_err(|e| format!("couldn't read {}: {e}", path.display()))?;
+	if bytes.contains(&0) {
+		return Err(format!("{} is a binary file", path.display()));
+	}
+	Ok(String::from_utf8_lossy(&bytes).into())
+}
+
+impl DiffView {
+	/// Start reading and comparing the files, big files take a while
+	pub fn open(ctx: &egui::Context, path: &Path, target: &Path) -> Self {
+		let language = path
+			.extension()
+			.map_or("txt".into(), |e| e.to_string_lossy().into_owned());
+		let mut loader = Loader::default();
+		let (left, right) = (path.to_path_buf(), target.to_path_buf());
+		loader.load(ctx, move || {
+			read_text(&left).and_then(|left| Ok(diff_rows(&left, &read_text(&right)?)))
+		});
+
+		Self {
+			path: path.into(),
+			target: target.into(),
+			rows: None,
+			language,
+			loader,
+		}
+	}
+}
+
+/// Highlighted line with the background of its change
+fn line_job(ui: &Ui, theme: &CodeTheme, line: &str, language: &str, background: Color32) -> LayoutJob {
+	let mut job = highlight(ui.ctx(), theme, line, language);
+	for section in &mut job.sections {
+		section.format.background = background;
+	}
+	job
+}
+
+fn side(ui: &mut Ui, theme: &CodeTheme, line: &Option<(usize, String)>, language: &str, background: Color32) {
+	match line {
+		Some((number, text)) => {
+			ui.horizontal(|ui| {
+				ui.label(egui::RichText::new(format!("{number:>4}")).monospace().weak());
+				ui.label(line_job(ui, theme, text, language, background));
+			});
+		}
+		None => {
+			ui.label("");
+		}
+	}
+}
+
+/// Window with the dotfile on the left and the deployed target on the right
+pub fn diff_window(app: &mut TemplateApp, ctx: &egui::Context) {
+	let Some(view) = &mut app.diff else {
+		return;
+	};
+	if let Some(rows) = view.loader.poll() {
+		view.rows = Some(rows);
+	}
+
+	let mut open = true;
+	egui::Window::new("Diff")
+		.default_size([650.0, 400.0])
+		.open(&mut open)
+		.show(ctx, |ui| {
+			ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 0.0);
+			ui.columns(2, |columns| {
+				columns[0].strong(view.path.display().to_string());
+				columns[1].strong(view.target.display().to_string());
+			});
+			ui.separator();
+
+			view.loader.spinner(ui, "comparing…");
+			let rows = match &view.rows {
+				None => return,
+				Some(Ok(rows)) => rows,
+				Some(Err(e)) => {
+					ui.label(e);
+					return;
+				}
+			};
+			if rows.iter().all(|r| !r.changed()) {
+				ui.label("the files are the same");
+			}
+
+			let theme = CodeTheme::from_style(ui.style());
+			egui::ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+				for row in rows {
+					let changed = row.changed();
+					ui.columns(2, |columns| {
+						let (left, right) = match changed {
+							true => (REMOVED, ADDED),
+							false => (Color32::TRANSPARENT, Color32::TRANSPARENT),
+						};
+						side(&mut columns[0], &theme, &row.left, &view.language, left);
+						side(&mut columns[1], &theme, &row.right, &view.language, right);
+					});
+				}
+			});
+		});
+
+	if !open {
+		app.diff = None;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type Side = Option<(usize, String)>;
+
+	/// (left, right) of every row with the line numbers
+	fn sides(left: &str, right: &str) -> Vec<(Side, Side)> {
+		diff_rows(left, right).into_iter().map(|r| (r.left, r.right)).collect()
+	}
+
+	fn line(n: usize, text: &str) -> Side {
+		Some((n, text.to_string()))
+	}
+
+	#[test]
+	fn same_files_have_no_changes() {
+		let rows = diff_rows("a\nb\n", "a\nb\n");
+		assert_eq!(rows.len(), 2);
+		assert!(rows.iter().all(|r| !r.changed()));
+	}
+
+	#[test]
+	fn changed_line_is_paired() {
+		assert_eq!(
+			sides("a\nb\nc", "a\nB\nc"),
+			[
+				(line(1, "a"), line(1, "a")),
+				(line(2, "b"), line(2, "B")),
+				(line(3, "c"), line(3, "c")),
+			]
+		);
+	}
+
+	#[test]
+	fn added_and_removed_lines_have_one_side() {
+		assert_eq!(
+			sides("a\nc", "a\nb\nc"),
+			[
+				(line(1, "a"), line(1, "a")),
+				(None, line(2, "b")),
+				(line(2, "c"), line(3, "c"))
+			]
+		);
+		assert_eq!(sides("a\nb", "a"), [(line(1, "a"), line(1, "a")), (line(2, "b"), None)]);
+	}
+
+	#[test]
+	fn empty_files() {
+		assert!(diff_rows("", "").is_empty());
+		assert_eq!(sides("", "a"), [(None, line(1, "a"))]);
+	}
+}
@@ -0,0 +1,239 @@
+use crate::app::TemplateApp;
+use egui::{Align2, Ui, ViewportCommand};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// A file open in the hook editer
+pub struct EditorTab {
+	pub path: PathBuf,
+	pub code: String,
+	/// the code as it is on disk
+	saved: String,
+}
+
+impl EditorTab {
+	/// If there are edits that aren't saved
+	pub fn dirty(&self) -> bool {
+		self.code != self.saved
+	}
+
+	/// `<group>/<file>`, hooks of different groups share file names
+	fn name(&self) -> String {
+		let file = self.path.file_name().unwrap_or_default().to_string_lossy();
+		match self.path.parent().and_then(|p| p.file_name()) {
+			Some(group) => format!("{}/{file}", group.to_string_lossy()),
+			None => file.into_owned(),
+		}
+	}
+
+	pub fn save(&mut self) -> io::Result<()> {
+		fs::write(&self.path, &self.code)?;
+		self.saved.clone_from(&self.code);
+		Ok(())
+	}
+}
+
+/// Open files of the hook editer
+#[derive(Default)]
+pub struct Editor {
+	pub tabs: Vec<EditorTab>,
+	active: usize,
+	/// tab waiting for the user to decide what happens to its edits
+	closing: Option<PathBuf>,
+	/// the window was closed with unsaved edits
+	quitting: bool,
+	/// close the window even with unsaved edits
+	quit: bool,
+}
+
+impl Editor {
+	/// Show `path` in a tab, an already open tab keeps its edits
+	pub fn open(&mut self, path: PathBuf, code: String) {
+		match self.tabs.iter().position(|t| t.path == path) {
+			Some(i) => self.active = i,
+			None => {
+				self.tabs.push(EditorTab {
+					path,
+					saved: code.clone(),
+					code,
+				});
+				self.active = self.tabs.len() - 1;
+			}
+		}
+	}
+
+	pub fn active(&self) -> Option<&EditorTab> {
+		self.tabs.get(self.active)
+	}
+
+	pub fn active_mut(&mut self) -> Option<&mut EditorTab> {
+		self.tabs.get_mut(self.active)
+	}
+
+	fn close(&mut self, i: usize) {
+		self.tabs.remove(i);
+		if i < self.active || self.active >= self.tabs.len() {
+			self.active = self.active.saturating_sub(1);
+		}
+	}
+
+	fn dirty(&self) -> bool {
+		self.tabs.iter().any(EditorTab::dirty)
+	}
+}
+
+/// Tabs of the open files, ● marks unsaved edits
+fn tab_bar(app: &mut TemplateApp, ui: &mut Ui) {
+	let editor = &mut app.editor;
+	let mut close = None;
+	ui.horizontal_wrapped(|ui| {
+		ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+		for (i, tab) in editor.tabs.iter().enumerate() {
+			let title = match tab.dirty() {
+				true => format!("● {}", tab.name()),
+				false => tab.name(),
+			};
+			if ui
+				.selectable_label(i == editor.active, title)
+				.on_hover_text(tab.path.display().to_string())
+				.clicked()
+			{
+				editor.active = i;
+			}
+			if ui.small_button("🗙").on_hover_text("close").clicked() {
+				close = Some(i);
+			}
+			ui.add_space(5.0);
+		}
+	});
+
+	match close {
+		Some(i) if editor.tabs[i].dirty() => editor.closing = Some(editor.tabs[i].path.clone()),
+		Some(i) => editor.close(i),
+		None => (),
+	}
+}
+
+pub fn code_editer(app: &mut TemplateApp, ui: &mut Ui) {
+	tab_bar(app, ui);
+	let Some(tab) = app.editor.active_mut() else {
+		return;
+	};
+
+	let theme = egui_extras::syntax_highlighting::CodeTheme::from_style(ui.style());
+	// todo patch egui_extras to use tmTheme file
+
+	let mut layouter = |ui: &egui::Ui, code: &str, wrap_width: f32| {
+		let mut layout_job = egui_extras::syntax_highlighting::highlight(ui.ctx(), &theme, code, "bash");
+		layout_job.wrap.max_width = wrap_width;
+		ui.fonts(|f| f.layout_job(layout_job))
+	};
+
+	egui::ScrollArea::vertical().show(ui, |ui| {
+		ui.add(
+			egui::TextEdit::multiline(&mut tab.code)
+				.id_source(&tab.path)
+				.font(egui::TextStyle::Monospace) // for cursor height
+				.code_editor()
+				.desired_rows(10)
+				.lock_focus(true)
+				.desired_width(f32::INFINITY)
+				.layouter(&mut layouter),
+		);
+	});
+}
+
+/// Asks what to do with unsaved edits when a tab or the window is closed
+pub fn unsaved_dialog(app: &mut TemplateApp, ctx: &egui::Context) {
+	let editor = &mut app.editor;
+	if ctx.input(|i| i.viewport().close_requested()) && editor.dirty() && !editor.quit {
+		ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+		editor.quitting = true;
+	}
+
+	// the tab might have been closed some other way since
+	let closing = editor
+		.closing
+		.as_ref()
+		.and_then(|path| editor.tabs.iter().position(|t| t.path == *path));
+	if editor.closing.is_some() && closing.is_none() {
+		editor.closing = None;
+	}
+
+	if let Some(i) = closing {
+		let mut choice = None;
+		egui::Window::new("Unsaved changes")
+			.collapsible(false)
+			.resizable(false)
+			.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+			.show(ctx, |ui| {
+				ui.label(format!("{} has unsaved changes", editor.tabs[i].name()));
+				ui.horizontal(|ui| {
+					for c in ["Save", "Discard", "Cancel"] {
+						if ui.button(c).clicked() {
+							choice = Some(c);
+						}
+					}
+				});
+			});
+
+		match choice {
+			Some("Save") => match editor.tabs[i].save() {
+				Ok(()) => editor.close(i),
+				Err(e) => app.output = format!("couldn't save {}: {e}", editor.tabs[i].path.display()),
+			},
+			Some("Discard") => editor.close(i),
+			_ => (),
+		}
+		if choice.is_some() {
+			editor.closing = None;
+		}
+	}
+
+	if editor.quitting {
+		let mut choice = None;
+		egui::Window::new("Quit with unsaved changes?")
+			.collapsible(false)
+			.resizable(false)
+			.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+			.show(ctx, |ui| {
+				for tab in editor.tabs.iter().filter(|t| t.dirty()) {
+					ui.label(format!("● {}", tab.name()));
+				}
+				ui.horizontal(|ui| {
+					for c in ["Save all and quit", "Quit without saving", "Cancel"] {
+						if ui.button(c).clicked() {
+							choice = Some(c);
+						}
+					}
+				});
+			});
+
+		let quit = match choice {
+			Some("Save all and quit") => {
+				let failed: Vec<_> = editor
+					.tabs
+					.iter_mut()
+					.filter(|t| t.dirty())
+					.filter_map(|t| {
+						t.save()
+							.err()
+							.map(|e| format!("couldn't save {}: {e}", t.path.display()))
+					})
+					.collect();
+				app.output = failed.join("\n");
+				failed.is_empty()
+			}
+			Some("Quit without saving") => true,
+			_ => false,
+		};
+		if choice.is_some() {
+			editor.quitting = false;
+		}
+		if quit {
+			editor.quit = true;
+			ctx.send_viewport_cmd(ViewportCommand::Close);
+		}
+	}
+}
@@ -0,0 +1,48 @@
+use egui::{Color32, Ui};
+use std::ffi::OsString;
+use std::io;
+use std::path::PathBuf;
+
+/// Why the dotfiles couldn't be read or changed
+#[derive(Debug, thiserror::Error)]
+pub enum UiError {
+	#[error("no dotfiles directory: {0}")]
+	NoDotfiles(String),
+	#[error("{} doesn't exist", .0.display())]
+	NoConfigs(PathBuf),
+	#[error("couldn't read {}: {source}", path.display())]
+	Unreadable { path: PathBuf, source: io::Error },
+	#[error("the group {} isn't valid UTF-8", .0.to_string_lossy())]
+	NonUtf8Group(OsString),
+	#[error("couldn't {action} {}: {source}", path.display())]
+	Failed {
+		action: &'static str,
+		path: PathBuf,
+		source: io::Error,
+	},
+	#[error("no directory to keep popped groups in")]
+	NoArchive,
+}
+
+impl UiError {
+	/// What the user can do about it
+	pub fn fix(&self) -> String {
+		match self {
+			UiError::NoDotfiles(_) => "Run Init to create one, or import a stow repo with From Stow".into(),
+			UiError::NoConfigs(path) => format!("Create {} with a directory for each group", path.display()),
+			UiError::Unreadable { path, .. } => format!("Check that you can read {}", path.display()),
+			UiError::NonUtf8Group(_) => "Rename the group's directory in Configs to a UTF-8 name".into(),
+			UiError::Failed { path, .. } => format!("Check that you can write to {}", path.display()),
+			UiError::NoArchive => "Set XDG_DATA_HOME or HOME to where popped groups can be kept".into(),
+		}
+	}
+}
+
+/// The error with its fix under it
+pub fn error_message(ui: &mut Ui, error: &UiError) {
+	ui.vertical(|ui| {
+		ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+		ui.colored_label(Color32::LIGHT_RED, error.to_string());
+		ui.label(error.fix());
+	});
+}
@@ -1,39 +1,114 @@
 use crate::app::{Page, TemplateApp, FOLDER_IMAGE};
+use crate::groups::home_dir;
+use crate::hooks::open_hook;
 use eframe::egui;
 use egui::{Image, Ui};
-use std::{fs, path::PathBuf};
+use std::path::PathBuf;
 
 pub fn push_file_picker(app: &mut TemplateApp, ui: &mut Ui) {
 	ui.style_mut().spacing.item_spacing = egui::vec2(15.0, 15.0);
 
+	let files = app.push_files.get_or_insert_with(Vec::new);
+	// dropped directories are pushed as a whole
+	let mut changed = add_files(files, pick_or_drop(ui, rfd::FileDialog::new()));
+	if ui.button("Open folder…").clicked() {
+		if let Some(folders) = rfd::FileDialog::new().pick_folders() {
+			changed |= add_files(files, folders);
+		}
+	}
+	changed |= file_list(ui, files);
+
+	if changed {
+		app.page = Page::Push(app.push_files.clone());
+	}
+
+	preview_files_being_dropped(ui.ctx());
+}
+
+pub fn encrypt_file_picker(app: &mut TemplateApp, ui: &mut Ui) {
+	ui.style_mut().spacing.item_spacing = egui::vec2(15.0, 15.0);
+
+	let files = app.encrypt_files.get_or_insert_with(Vec::new);
+	let mut changed = add_files(files, pick_or_drop(ui, rfd::FileDialog::new()));
+	changed |= file_list(ui, files);
+
+	if changed {
+		app.page = Page::Encrypt(app.encrypt_files.clone());
+	}
+
+	preview_files_being_dropped(ui.ctx());
+}
+
+pub fn lookup_file_picker(app: &mut TemplateApp, ui: &mut Ui) {
+	ui.style_mut().spacing.item_spacing = egui::vec2(15.0, 15.0);
+
+	let dialog = match home_dir() {
+		Some(home) => rfd::FileDialog::new().set_directory(home),
+		None => rfd::FileDialog::new(),
+	};
+	let files = app.lookup_files.get_or_insert_with(Vec::new);
+	if add_files(files, pick_or_drop(ui, dialog)) {
+		app.page = Page::GroupIs(app.lookup_files.clone());
+	}
+
+	preview_files_being_dropped(ui.ctx());
+}
+
+/// Files picked with `dialog` or dropped on the window this frame
+fn pick_or_drop(ui: &mut Ui, dialog: rfd::FileDialog) -> Vec<PathBuf> {
 	// icons
 	let folder_icon = Image::new(FOLDER_IMAGE).fit_to_original_size(1.05);
 
+	let mut paths = Vec::new();
 	if ui
 		.add(egui::Button::image_and_text(folder_icon, "Open file…"))
 		.clicked()
 	{
-		if let Some(path) = rfd::FileDialog::new().pick_files() {
-			app.push_files = Some(path.iter().filter_map(|p| p.to_str().map(|p| p.to_string())).collect());
-			app.page = Page::Push(app.push_files.take())
+		if let Some(picked) = dialog.pick_files() {
+			paths = picked;
 		}
 	}
 
-	if let Some(opened_hook) = &app.opened_hook {
-		ui.horizontal(|ui| {
-			ui.label("Picked file:");
-			ui.label(opened_hook);
-		});
-	}
+	// Collect dropped files:
+	ui.ctx()
+		.input(|i| paths.extend(i.raw.dropped_files.iter().filter_map(|f| f.path.clone())));
+	paths
+}
 
-	preview_files_being_dropped(app, ui.ctx());
+/// Add paths that aren't in `files` yet, returns true if any were added
+fn add_files(files: &mut Vec<String>, paths: Vec<PathBuf>) -> bool {
+	let len = files.len();
+	for path in paths {
+		let path = path.display().to_string();
+		if !files.contains(&path) {
+			files.push(path);
+		}
+	}
+	files.len() != len
+}
 
-	// Collect dropped files:
-	ui.ctx().input(|i| {
-		if !i.raw.dropped_files.is_empty() {
-			app.dropped_files.clone_from(&i.raw.dropped_files);
+/// List of picked files with a button to remove each, returns true if one was removed
+pub fn file_list(ui: &mut Ui, files: &mut Vec<String>) -> bool {
+	let mut remove = None;
+	ui.vertical(|ui| {
+		ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+		for (i, file) in files.iter().enumerate() {
+			ui.horizontal(|ui| {
+				if ui.small_button("🗙").on_hover_text("remove").clicked() {
+					remove = Some(i);
+				}
+				ui.label(file);
+			});
 		}
 	});
+
+	match remove {
+		Some(i) => {
+			files.remove(i);
+			true
+		}
+		None => false,
+	}
 }
 
 pub fn hook_file_picker(app: &mut TemplateApp, ui: &mut Ui, hooks_dir: Option<PathBuf>) {
@@ -51,33 +126,23 @@ pub fn hook_file_picker(app: &mut TemplateApp, ui: &mut Ui, hooks_dir: Option<Pa
 			.set_directory(hooks_dir.unwrap_or(PathBuf::from("/")))
 			.pick_file()
 		{
-			app.opened_hook = Some(path.display().to_string());
 			// set the contens of the code window to the selected file
-			if let Some(s) = &app.opened_hook {
-				app.code = String::from_utf8_lossy(&fs::read(s).unwrap()).into()
-			}
+			open_hook(app, &path);
 		}
 	}
 
-	if let Some(opened_hook) = &app.opened_hook {
+	if let Some(tab) = app.editor.active() {
 		ui.horizontal(|ui| {
 			ui.label("Picked file:");
-			ui.label(opened_hook);
+			ui.label(tab.path.display().to_string());
 		});
 	}
 
-	preview_files_being_dropped(app, ui.ctx());
-
-	// Collect dropped files:
-	ui.ctx().input(|i| {
-		if !i.raw.dropped_files.is_empty() {
-			app.dropped_files.clone_from(&i.raw.dropped_files);
-		}
-	});
+	preview_files_being_dropped(ui.ctx());
 }
 
 /// Preview hovering files:
-fn preview_files_being_dropped(app: &mut TemplateApp, ctx: &egui::Context) {
+fn preview_files_being_dropped(ctx: &egui::Context) {
 	use egui::{Align2, Color32, Id, LayerId, Order, TextStyle};
 	use std::fmt::Write as _;
 
@@ -96,10 +161,7 @@ fn preview_files_being_dropped(app: &mut TemplateApp, ctx: &egui::Context) {
 			text
 		});
 
-		let painter = ctx.layer_painter(LayerId::new(
-			Order::Foreground,
-			Id::new(format!("file_drop_target: {}", app.check_count)),
-		));
+		let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("file_drop_target")));
 
 		let screen_rect = ctx.screen_rect();
 		painter.rect_filled(screen_rect, 0.0, Color32::from_black_alpha(192));
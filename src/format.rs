@@ -0,0 +1,63 @@
+/// Size in bytes as B, KiB, MiB or GiB
+pub fn format_size(size: u64) -> String {
+	const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+	let mut size = size as f64;
+	let mut unit = 0;
+	while size >= 1024.0 && unit < UNITS.len() - 1 {
+		size /= 1024.0;
+		unit += 1;
+	}
+
+	match unit {
+		0 => format!("{size} {}", UNITS[0]),
+		_ => format!("{size:.1} {}", UNITS[unit]),
+	}
+}
+
+/// `YYYY-MM-DD HH:MM` in UTC
+pub fn format_time(time: std::time::SystemTime) -> String {
+	let secs = time.duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+	let (days, secs) = (secs / 86400, secs % 86400);
+
+	// civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+	let z = days + 719468;
+	let era = z / 146097;
+	let doe = z % 146097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + u64::from(month <= 2);
+
+	format!("{year}-{month:02}-{day:02} {:02}:{:02}", secs / 3600, secs % 3600 / 60)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::{Duration, UNIX_EPOCH};
+
+	#[test]
+	fn sizes() {
+		assert_eq!(format_size(0), "0 B");
+		assert_eq!(format_size(1023), "1023 B");
+		assert_eq!(format_size(1024), "1.0 KiB");
+		assert_eq!(format_size(1536), "1.5 KiB");
+		assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+		assert_eq!(format_size(3 * 1024 * 1024 * 1024 * 1024), "3072.0 GiB");
+	}
+
+	#[test]
+	fn times() {
+		assert_eq!(format_time(UNIX_EPOCH), "1970-01-01 00:00");
+		assert_eq!(
+			format_time(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+			"2000-02-29 00:00"
+		);
+		assert_eq!(
+			format_time(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+			"2023-11-14 22:13"
+		);
+	}
+}
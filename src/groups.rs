@@ -1,31 +1,262 @@
-use tuckr::dotfiles::{self, ReturnCode};
-
-/// Exclude groups sufixed with _<os_name>
-// fn default_exclude() -> Vec<String> {
-// 	tuckr::dotfiles::group_ends_with_target_name(group)
-// }
-pub fn load_groups(output: &mut String) -> Result<Vec<String>, ReturnCode> {
-	let dotfiles_dir = match dotfiles::get_dotfiles_path(output) {
-		Ok(path) => path,
-		Err(e) => {
-			eprintln!("{e}");
-			return Err(ReturnCode::NoSetupFolder);
+use crate::error::UiError;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use tuckr::dotfiles;
+
+/// The dotfiles directory tuckr uses
+pub fn dotfiles_dir() -> Result<PathBuf, UiError> {
+	dotfiles::get_dotfiles_path(&mut "".into()).map_err(|e| UiError::NoDotfiles(e.to_string()))
+}
+
+/// `Configs/` in the dotfiles, it has a directory for every group
+pub fn configs_dir() -> Result<PathBuf, UiError> {
+	Ok(dotfiles_dir()?.join("Configs"))
+}
+
+/// Every group in `Configs/`, OS variants sorted after their base group.
+/// Groups that can't be used are skipped and returned with why
+pub fn find_groups() -> LoadedGroups {
+	let configs_dir = configs_dir()?;
+
+	let entries = configs_dir.read_dir().map_err(|source| match source.kind() {
+		ErrorKind::NotFound => UiError::NoConfigs(configs_dir.clone()),
+		_ => UiError::Unreadable {
+			path: configs_dir.clone(),
+			source,
+		},
+	})?;
+
+	let mut groups = Vec::new();
+	let mut skipped = Vec::new();
+	for entry in entries {
+		let entry = entry.map_err(|source| UiError::Unreadable {
+			path: configs_dir.clone(),
+			source,
+		})?;
+		let file_type = entry.file_type().map_err(|source| UiError::Unreadable {
+			path: entry.path(),
+			source,
+		})?;
+		if file_type.is_dir() {
+			match entry.file_name().into_string() {
+				Ok(group) => groups.push(group),
+				Err(name) => skipped.push(UiError::NonUtf8Group(name)),
+			}
 		}
 	}
-	.join("Configs");
-
-	let groups: Vec<_> = dotfiles_dir
-		.read_dir()
-		.unwrap()
-		.filter_map(|f| {
-			let f = f.unwrap();
-			if f.file_type().unwrap().is_dir() {
-				Some(f.file_name().into_string().unwrap())
-			} else {
-				None
-			}
-		})
-		.collect();
 
-	Ok(groups)
+	groups.sort_by(|a, b| (base_name(a), group_os(a), a).cmp(&(base_name(b), group_os(b), b)));
+	Ok((groups, skipped))
+}
+
+/// What `find_groups` returned, (groups, skipped groups)
+pub type LoadedGroups = Result<(Vec<String>, Vec<UiError>), UiError>;
+
+/// Every group in `Configs/` that can be used
+pub fn load_groups() -> Result<Vec<String>, UiError> {
+	find_groups().map(|(groups, _)| groups)
+}
+
+/// The users home directory, dotfiles are deployed relative to it
+pub fn home_dir() -> Option<PathBuf> {
+	std::env::var_os("HOME")
+		.or_else(|| std::env::var_os("USERPROFILE"))
+		.map(PathBuf::from)
+}
+
+/// Where `file` from `group_dir` ends up in the home directory
+pub fn target_path(group_dir: &Path, file: &Path) -> Option<PathBuf> {
+	Some(home_dir()?.join(file.strip_prefix(group_dir).ok()?))
+}
+
+#[cfg(unix)]
+pub fn symlink(source: &Path, target: &Path) -> io::Result<()> {
+	std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+pub fn symlink(source: &Path, target: &Path) -> io::Result<()> {
+	match source.is_dir() {
+		true => std::os::windows::fs::symlink_dir(source, target),
+		false => std::os::windows::fs::symlink_file(source, target),
+	}
+}
+
+/// Every file under `dir` including ones in sub directories, sorted by path
+pub fn walk_files(dir: &Path) -> Vec<PathBuf> {
+	let mut files = Vec::new();
+	let Ok(entries) = dir.read_dir() else {
+		return files;
+	};
+
+	for entry in entries.flatten() {
+		let path = entry.path();
+		match entry.file_type() {
+			Ok(t) if t.is_dir() => files.append(&mut walk_files(&path)),
+			Ok(_) => files.push(path),
+			Err(_) => (),
+		}
+	}
+
+	files.sort();
+	files
+}
+
+/// Copy a file, a symlink itself or a directory with everything in it
+pub fn copy_path(from: &Path, to: &Path) -> io::Result<()> {
+	if let Some(parent) = to.parent() {
+		fs::create_dir_all(parent)?;
+	}
+	let metadata = from.symlink_metadata()?;
+	if metadata.is_symlink() {
+		symlink(&fs::read_link(from)?, to)
+	} else if metadata.is_dir() {
+		for path in walk_files(from) {
+			copy_path(&path, &to.join(path.strip_prefix(from).unwrap_or(&path)))?;
+		}
+		Ok(())
+	} else {
+		fs::copy(from, to).map(|_| ())
+	}
+}
+
+/// Rename that falls back to copying across file systems
+pub fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+	if fs::rename(from, to).is_ok() {
+		return Ok(());
+	}
+	copy_path(from, to)?;
+	match from.symlink_metadata()?.is_dir() {
+		true => fs::remove_dir_all(from),
+		false => fs::remove_file(from),
+	}
+}
+
+/// OSes and OS families a new group can be made for
+pub const OS_SUFFIXES: [&str; 12] = [
+	"linux",
+	"macos",
+	"windows",
+	"unix",
+	"freebsd",
+	"openbsd",
+	"netbsd",
+	"dragonfly",
+	"android",
+	"ios",
+	"solaris",
+	"illumos",
+];
+
+/// The OS a group is suffixed with, `None` if it isn't for a specific OS
+pub fn group_os(group: &str) -> Option<&str> {
+	let (_, os) = group.rsplit_once('_')?;
+	// the same check tuckr makes, so a group is valid here exactly when tuckr deploys it
+	dotfiles::group_ends_with_target_name(group).then_some(os)
+}
+
+/// If the group can be deployed on this OS, groups without a suffix always can
+pub fn is_valid_target(group: &str) -> bool {
+	match group_os(group) {
+		Some(os) => os == std::env::consts::OS || os == std::env::consts::FAMILY,
+		None => true,
+	}
+}
+
+/// The group without its OS suffix, shared by all variants of a group
+pub fn base_name(group: &str) -> &str {
+	match group_os(group) {
+		Some(os) => &group[..group.len() - os.len() - 1],
+		None => group,
+	}
+}
+
+/// Groups for other OSes, excluded from add, set and rm by default
+pub fn default_exclude(groups: &[String]) -> Vec<String> {
+	groups.iter().filter(|g| !is_valid_target(g)).cloned().collect()
+}
+
+/// The name of a new group with the OS suffix, or why it can't be created
+pub fn new_group_name(name: &str, os: Option<&str>, groups: &[String]) -> Result<String, String> {
+	if name.is_empty() {
+		return Err("enter a name".into());
+	}
+	if name.starts_with('.') {
+		return Err("can't start with a dot".into());
+	}
+	if let Some(c) = name
+		.chars()
+		.find(|c| r#"/\<>:"|?*"#.contains(*c) || c.is_whitespace() || c.is_control())
+	{
+		return Err(format!("can't contain {c:?}"));
+	}
+
+	let name = match os {
+		Some(os) => format!("{name}_{os}"),
+		None => name.to_string(),
+	};
+	match groups.contains(&name) {
+		true => Err(format!("{name} already exists")),
+		false => Ok(name),
+	}
+}
+
+/// Create the empty group `Configs/<name>`
+pub fn create_group(name: &str) -> Result<(), UiError> {
+	let path = configs_dir()?.join(name);
+	fs::create_dir_all(&path).map_err(|source| UiError::Failed {
+		action: "create",
+		path,
+		source,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn os_of_a_group() {
+		assert_eq!(group_os("zsh_linux"), Some("linux"));
+		assert_eq!(group_os("my_vim_macos"), Some("macos"));
+		assert_eq!(group_os("zsh"), None);
+		assert_eq!(group_os("my_vim"), None);
+		assert_eq!(group_os("_linux"), Some("linux"));
+	}
+
+	#[test]
+	fn base_name_drops_the_os() {
+		assert_eq!(base_name("zsh_linux"), "zsh");
+		assert_eq!(base_name("my_vim_windows"), "my_vim");
+		assert_eq!(base_name("my_vim"), "my_vim");
+		assert_eq!(base_name("zsh"), "zsh");
+	}
+
+	#[test]
+	fn new_group_names() {
+		let groups = vec!["zsh".to_string(), "vim_linux".to_string()];
+		assert_eq!(new_group_name("nvim", None, &groups), Ok("nvim".into()));
+		assert_eq!(new_group_name("vim", Some("macos"), &groups), Ok("vim_macos".into()));
+		assert_eq!(new_group_name("zsh", None, &groups), Err("zsh already exists".into()));
+		assert_eq!(
+			new_group_name("vim", Some("linux"), &groups),
+			Err("vim_linux already exists".into())
+		);
+		assert_eq!(new_group_name("", None, &groups), Err("enter a name".into()));
+		assert_eq!(
+			new_group_name(".vim", None, &groups),
+			Err("can't start with a dot".into())
+		);
+		assert_eq!(new_group_name("a/b", None, &groups), Err("can't contain '/'".into()));
+		assert_eq!(new_group_name("a b", None, &groups), Err("can't contain ' '".into()));
+	}
+
+	#[test]
+	fn new_groups_get_the_os() {
+		let name = new_group_name("git", Some("windows"), &[]).unwrap();
+		assert_eq!(group_os(&name), Some("windows"));
+		assert_eq!(base_name(&name), "git");
+		assert_eq!(group_os(&new_group_name("git", None, &[]).unwrap()), None);
+	}
 }
@@ -0,0 +1,199 @@
+use crate::app::{Page, TemplateApp};
+use crate::cmd::args;
+use crate::format::format_time;
+use egui::{Color32, RichText, Ui};
+use std::time::SystemTime;
+use tuckr::Cli;
+
+/// Older commands are dropped so the saved state stays small
+const MAX_HISTORY: usize = 200;
+/// Only the end of long outputs is kept for the same reason
+const MAX_OUTPUT: usize = 4000;
+
+/// A command that was run and what it printed
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct HistoryEntry {
+	/// the page and groups the command is run again with
+	pub page: Page,
+	pub groups: Vec<String>,
+	/// the command as it would be typed in a terminal
+	pub command: String,
+	pub time: SystemTime,
+	pub success: bool,
+	pub output: String,
+}
+
+impl HistoryEntry {
+	/// None for commands that aren't run from a page
+	pub fn new(cli: &Cli) -> Option<Self> {
+		let (page, groups) = page_of(cli)?;
+		Some(Self {
+			page,
+			groups,
+			command: command_line(cli),
+			time: SystemTime::now(),
+			success: false,
+			output: String::new(),
+		})
+	}
+}
+
+/// The last `MAX_OUTPUT` bytes of `output`, cut at a character
+fn truncate(output: &str) -> String {
+	if output.len() <= MAX_OUTPUT {
+		return output.to_string();
+	}
+	let mut start = output.len() - MAX_OUTPUT;
+	while !output.is_char_boundary(start) {
+		start += 1;
+	}
+	format!("…{}", &output[start..])
+}
+
+/// Add a finished command to the history
+pub fn record(app: &mut TemplateApp, mut entry: HistoryEntry) {
+	entry.output = truncate(&entry.output);
+	app.history.push(entry);
+	if app.history.len() > MAX_HISTORY {
+		app.history.remove(0);
+	}
+}
+
+/// The page and groups `cli` is made from, the reverse of `Page::into_cli`
+pub fn page_of(cli: &Cli) -> Option<(Page, Vec<String>)> {
+	let all = || vec![r"\*".to_string()];
+	Some(match cli {
+		Cli::Add {
+			groups,
+			exclude,
+			force,
+			adopt,
+		} => (Page::Add(Some(exclude.clone()), *force, *adopt), groups.clone()),
+		Cli::Rm { groups, exclude } => (Page::Rm(Some(exclude.clone())), groups.clone()),
+		Cli::Set {
+			groups,
+			exclude,
+			force,
+			adopt,
+		} => (Page::Set(Some(exclude.clone()), *force, *adopt), groups.clone()),
+		Cli::Push { group, files } => (Page::Push(Some(files.clone())), vec![group.clone()]),
+		Cli::Pop { groups } => (Page::Pop, groups.clone()),
+		Cli::Init => (Page::Init, all()),
+		Cli::Status { groups } => (Page::Status, groups.clone().unwrap_or_else(all)),
+		Cli::Encrypt { group, dotfiles } => (Page::Encrypt(Some(dotfiles.clone())), vec![group.clone()]),
+		Cli::Decrypt { groups, exclude } => (Page::Decrypt(Some(exclude.clone())), groups.clone()),
+		Cli::GroupIs { files } => (Page::GroupIs(Some(files.clone())), all()),
+		Cli::LsSecrets => (Page::Secrets, all()),
+		Cli::FromStow | Cli::LsHooks => return None,
+	})
+}
+
+/// Quote `arg` for a posix shell if it needs it
+fn quote(arg: &str) -> String {
+	let plain = |c: char| c.is_alphanumeric() || r"-_./\*~=+:,@%".contains(c);
+	match !arg.is_empty() && arg.chars().all(plain) {
+		true => arg.to_string(),
+		false => format!("'{}'", arg.replace('\'', r"'\''")),
+	}
+}
+
+/// The tuckr command line that does the same as `cli`
+pub fn command_line(cli: &Cli) -> String {
+	let mut line = String::from("tuckr");
+	for arg in args(cli) {
+		line.push(' ');
+		line.push_str(&quote(&arg));
+	}
+	line
+}
+
+/// Every command run, newest first, to look at, copy or run again
+pub fn history_page(app: &mut TemplateApp, ui: &mut Ui) {
+	if app.history.is_empty() {
+		ui.label("Nothing has been run yet");
+		return;
+	}
+	if ui.button("Clear history").clicked() {
+		app.history.clear();
+		return;
+	}
+
+	let mut rerun = None;
+	egui::ScrollArea::vertical()
+		.id_source("history")
+		.max_height(350.0)
+		.show(ui, |ui| {
+			for (i, entry) in app.history.iter().enumerate().rev() {
+				let header = format!("{}  {}", format_time(entry.time), entry.command);
+				let header = match entry.success {
+					true => RichText::new(header),
+					false => RichText::new(header).color(Color32::LIGHT_RED),
+				};
+				egui::CollapsingHeader::new(header)
+					.id_source(("history", i))
+					.show(ui, |ui| {
+						ui.horizontal(|ui| {
+							ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+							if ui
+								.button("Copy")
+								.on_hover_text("copy as a tuckr command line")
+								.clicked()
+							{
+								ui.ctx().copy_text(entry.command.clone());
+							}
+							if ui.button("Re-run").clicked() {
+								rerun = Some(i);
+							}
+						});
+						ui.label(RichText::new(&entry.output).monospace());
+					});
+			}
+		});
+
+	if let Some(i) = rerun {
+		let entry = app.history[i].clone();
+		app.run_page(ui.ctx(), entry.page, entry.groups);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn quoting() {
+		assert_eq!(quote("nvim"), "nvim");
+		assert_eq!(quote(r"\*"), r"\*");
+		assert_eq!(quote("~/.config/nvim"), "~/.config/nvim");
+		assert_eq!(quote("my file"), "'my file'");
+		assert_eq!(quote("it's"), r"'it'\''s'");
+		assert_eq!(quote("$HOME"), "'$HOME'");
+		assert_eq!(quote(""), "''");
+	}
+
+	#[test]
+	fn command_lines() {
+		let add = Cli::Add {
+			groups: vec!["nvim".into(), "zsh".into()],
+			exclude: vec!["a".into(), "b".into()],
+			force: true,
+			adopt: false,
+		};
+		assert_eq!(command_line(&add), "tuckr add nvim zsh --exclude a,b --force");
+
+		let group_is = Cli::GroupIs {
+			files: vec!["/home/me/my file".into()],
+		};
+		assert_eq!(command_line(&group_is), "tuckr groupis '/home/me/my file'");
+
+		assert_eq!(command_line(&Cli::LsHooks), "tuckr ls-hooks");
+		assert_eq!(command_line(&Cli::Status { groups: None }), "tuckr status");
+		assert_eq!(
+			command_line(&Cli::Push {
+				group: "git".into(),
+				files: vec!["~/.gitconfig".into()],
+			}),
+			"tuckr push git ~/.gitconfig"
+		);
+	}
+}
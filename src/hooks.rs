@@ -0,0 +1,422 @@
+use crate::app::{HookType, TemplateApp};
+use crate::cmd::add_cmd;
+use crate::groups::{dotfiles_dir, is_valid_target, load_groups};
+use crate::loader::Loader;
+use crate::worker::Cancel;
+use egui::{Color32, RichText, Ui};
+use std::fs;
+use std::io::{self, BufRead, BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+/// Hook scripts of one group in `Hooks/`
+pub struct GroupHooks {
+	pub group: String,
+	pub pre: Vec<PathBuf>,
+	pub post: Vec<PathBuf>,
+}
+
+impl GroupHooks {
+	fn is_empty(&self) -> bool {
+		self.pre.is_empty() && self.post.is_empty()
+	}
+}
+
+/// Hooks of every group, read in the background when the hooks page lists them
+#[derive(Default)]
+pub struct HooksView {
+	/// None until the first read came back
+	hooks: Option<Vec<GroupHooks>>,
+	/// the hooks were asked for since they last changed
+	requested: bool,
+	loader: Loader<Vec<GroupHooks>>,
+}
+
+impl HooksView {
+	/// Read the hooks again on the next frame
+	pub fn reload(&mut self) {
+		self.requested = false;
+	}
+}
+
+/// The stage a hook runs in, tuckr picks it from the file name prefix
+pub fn hook_type(hook: &Path) -> Option<HookType> {
+	let name = hook.file_name()?.to_str()?;
+	if name.starts_with("pre") {
+		Some(HookType::Pre)
+	} else if name.starts_with("post") {
+		Some(HookType::Post)
+	} else {
+		None
+	}
+}
+
+/// Hooks of every group in `Hooks/` and of `groups` that have none, sorted by group
+pub fn list_hooks(hooks_dir: &Path, groups: &[String]) -> Vec<GroupHooks> {
+	let mut hooks: Vec<_> = groups
+		.iter()
+		.map(|group| GroupHooks {
+			group: group.clone(),
+			pre: Vec::new(),
+			post: Vec::new(),
+		})
+		.collect();
+
+	for entry in hooks_dir.read_dir().into_iter().flatten().flatten() {
+		let Ok(group) = entry.file_name().into_string() else {
+			continue;
+		};
+		let i = match hooks.iter().position(|h| h.group == group) {
+			Some(i) => i,
+			None => {
+				hooks.push(GroupHooks {
+					group,
+					pre: Vec::new(),
+					post: Vec::new(),
+				});
+				hooks.len() - 1
+			}
+		};
+
+		for hook in entry.path().read_dir().into_iter().flatten().flatten() {
+			let path = hook.path();
+			match hook_type(&path) {
+				Some(HookType::Pre) => hooks[i].pre.push(path),
+				Some(HookType::Post) => hooks[i].post.push(path),
+				None => (),
+			}
+		}
+		hooks[i].pre.sort();
+		hooks[i].post.sort();
+	}
+
+	hooks.sort_by(|a, b| a.group.cmp(&b.group));
+	hooks
+}
+
+/// Create an empty hook script that can be run, an existing one is kept as it is
+pub fn create_hook(hook: &Path) -> io::Result<()> {
+	let mut options = fs::OpenOptions::new();
+	options.append(true).create(true);
+	// tuckr runs hooks directly, so they need the executable bit
+	#[cfg(unix)]
+	std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o755);
+	options.open(hook).map(|_| ())
+}
+
+/// Load a hook script into the code editer
+pub fn open_hook(app: &mut TemplateApp, hook: &Path) {
+	match fs::read(hook) {
+		Ok(code) => {
+			app.editor
+				.open(hook.to_path_buf(), String::from_utf8_lossy(&code).into());
+		}
+		Err(e) => app.output = format!("couldn't open {}: {e}", hook.display()),
+	}
+}
+
+/// Table of the pre and post hooks of every group, click a hook to edit it
+pub fn hooks_browser(app: &mut TemplateApp, ui: &mut Ui, hooks_dir: &Path) {
+	let view = &mut app.hooks;
+	if !view.requested {
+		let hooks_dir = hooks_dir.to_path_buf();
+		let groups = app.found_groups.clone().unwrap_or_default();
+		view.loader.load(ui.ctx(), move || list_hooks(&hooks_dir, &groups));
+		view.requested = true;
+	}
+	if let Some(hooks) = view.loader.poll() {
+		view.hooks = Some(hooks);
+	}
+	view.loader.spinner(ui, "loading…");
+	let Some(hooks) = &view.hooks else {
+		return;
+	};
+
+	let mut open = None;
+	egui::CollapsingHeader::new("Hooks").default_open(true).show(ui, |ui| {
+		egui::ScrollArea::vertical()
+			.id_source("hooks_browser")
+			.max_height(150.0)
+			.show(ui, |ui| {
+				egui::Grid::new("hooks_browser_grid")
+					.striped(true)
+					.num_columns(3)
+					.show(ui, |ui| {
+						ui.strong("Group");
+						ui.strong("Pre");
+						ui.strong("Post");
+						ui.end_row();
+
+						for group in hooks.iter() {
+							ui.label(&group.group);
+							if group.is_empty() {
+								ui.colored_label(Color32::GRAY, "no hooks");
+								ui.end_row();
+								continue;
+							}
+
+							for stage in [&group.pre, &group.post] {
+								ui.vertical(|ui| {
+									ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+									for hook in stage {
+										let name = hook.file_name().unwrap_or_default().to_string_lossy();
+										let opened = app.editor.active().is_some_and(|t| t.path == *hook);
+										if ui.selectable_label(opened, name).clicked() {
+											open = Some(hook.clone());
+										}
+									}
+								});
+							}
+							ui.end_row();
+						}
+					});
+			});
+	});
+
+	if let Some(hook) = open {
+		open_hook(app, &hook);
+	}
+}
+
+/// Sent from `run_set` while hooks run
+pub enum HookEvent {
+	Start {
+		group: String,
+		stage: HookType,
+		hook: String,
+	},
+	Line {
+		text: String,
+		stderr: bool,
+	},
+	/// exit code, None if the hook was killed
+	Exit(Option<i32>),
+}
+
+/// Send every line of `pipe` to the log as it's printed
+fn stream_lines(
+	pipe: impl Read + Send + 'static, stderr: bool, tx: Sender<HookEvent>, ctx: egui::Context,
+) -> JoinHandle<()> {
+	thread::spawn(move || {
+		for text in BufReader::new(pipe).lines().map_while(Result::ok) {
+			let _ = tx.send(HookEvent::Line { text, stderr });
+			ctx.request_repaint();
+		}
+	})
+}
+
+/// Run a hook streaming its output, returns true if it succeeded
+fn run_hook(
+	group: &str, stage: HookType, hook: &Path, tx: &Sender<HookEvent>, ctx: &egui::Context, cancel: &Cancel,
+) -> bool {
+	let _ = tx.send(HookEvent::Start {
+		group: group.into(),
+		stage,
+		hook: hook.file_name().unwrap_or_default().to_string_lossy().into(),
+	});
+
+	// run like tuckr does, a hook without the executable bit fails
+	let child = Command::new(hook)
+		.stdin(Stdio::null())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn();
+
+	let mut child = match child {
+		Ok(child) => child,
+		Err(e) => {
+			let text = match e.kind() {
+				ErrorKind::PermissionDenied => format!("{e}, make the hook executable"),
+				_ => e.to_string(),
+			};
+			let _ = tx.send(HookEvent::Line { text, stderr: true });
+			let _ = tx.send(HookEvent::Exit(None));
+			return false;
+		}
+	};
+
+	let stdout = child
+		.stdout
+		.take()
+		.map(|out| stream_lines(out, false, tx.clone(), ctx.clone()));
+	let stderr = child
+		.stderr
+		.take()
+		.map(|err| stream_lines(err, true, tx.clone(), ctx.clone()));
+	// killed when the job is cancelled, which closes the pipes
+	let code = cancel.wait(child).ok().and_then(|status| status.code());
+	for reader in [stdout, stderr].into_iter().flatten() {
+		let _ = reader.join();
+	}
+
+	let _ = tx.send(HookEvent::Exit(code));
+	ctx.request_repaint();
+	code == Some(0)
+}
+
+/// `tuckr set` run by the ui so hook output can be streamed to the log,
+/// each group runs its pre hooks, is added and then runs its post hooks.
+/// Like tuckr it skips excluded groups and groups for other OSes, each is reported
+pub fn run_set(
+	groups: Vec<String>, exclude: Vec<String>, force: bool, adopt: bool, tx: Sender<HookEvent>, ctx: egui::Context,
+	cancel: &Cancel,
+) -> (String, ExitCode) {
+	let mut output = String::new();
+	let groups = match groups.iter().any(|g| g == r"\*") {
+		true => match load_groups() {
+			Ok(groups) => groups,
+			Err(e) => return (e.to_string(), ExitCode::FAILURE),
+		},
+		false => groups,
+	};
+	let hooks_dir = match dotfiles_dir() {
+		Ok(p) => p.join("Hooks"),
+		Err(e) => return (e.to_string(), ExitCode::FAILURE),
+	};
+	let hooks = list_hooks(&hooks_dir, &[]);
+
+	for group in &groups {
+		if exclude.contains(group) {
+			output.push_str(&format!("skipped {group}, it's excluded\n"));
+			continue;
+		}
+		if !is_valid_target(group) {
+			output.push_str(&format!("skipped {group}, it's for another OS\n"));
+			continue;
+		}
+		let group_hooks = hooks.iter().find(|h| &h.group == group);
+
+		for hook in group_hooks.map(|h| h.pre.as_slice()).unwrap_or_default() {
+			if cancel.cancelled() {
+				return (output, ExitCode::FAILURE);
+			}
+			if !run_hook(group, HookType::Pre, hook, &tx, &ctx, cancel) {
+				output.push_str(&format!("{} of {group} failed\n", hook.display()));
+				return (output, ExitCode::FAILURE);
+			}
+		}
+
+		if cancel.cancelled() {
+			return (output, ExitCode::FAILURE);
+		}
+		let (out, code) = add_cmd(std::slice::from_ref(group), &[], force, adopt);
+		output.push_str(&out);
+		if code != ExitCode::SUCCESS {
+			return (output, code);
+		}
+
+		for hook in group_hooks.map(|h| h.post.as_slice()).unwrap_or_default() {
+			if cancel.cancelled() {
+				return (output, ExitCode::FAILURE);
+			}
+			if !run_hook(group, HookType::Post, hook, &tx, &ctx, cancel) {
+				output.push_str(&format!("{} of {group} failed\n", hook.display()));
+				return (output, ExitCode::FAILURE);
+			}
+		}
+	}
+
+	(output, ExitCode::SUCCESS)
+}
+
+/// Output of one hook
+struct LogSection {
+	group: String,
+	stage: HookType,
+	hook: String,
+	/// (line, from stderr)
+	lines: Vec<(String, bool)>,
+	/// None while running, then the exit code or None if it was killed
+	exit: Option<Option<i32>>,
+}
+
+/// Output of the hooks run by the last set
+#[derive(Default)]
+pub struct HookLog {
+	rx: Option<Receiver<HookEvent>>,
+	sections: Vec<LogSection>,
+}
+
+impl HookLog {
+	/// Clear the log for a new run, hooks send their output to the returned sender
+	pub fn start(&mut self) -> Sender<HookEvent> {
+		let (tx, rx) = mpsc::channel();
+		self.rx = Some(rx);
+		self.sections.clear();
+		tx
+	}
+
+	fn poll(&mut self) {
+		let Some(rx) = &self.rx else {
+			return;
+		};
+
+		for event in rx.try_iter() {
+			match event {
+				HookEvent::Start { group, stage, hook } => self.sections.push(LogSection {
+					group,
+					stage,
+					hook,
+					lines: Vec::new(),
+					exit: None,
+				}),
+				HookEvent::Line { text, stderr } => {
+					if let Some(section) = self.sections.last_mut() {
+						section.lines.push((text, stderr));
+					}
+				}
+				HookEvent::Exit(code) => {
+					if let Some(section) = self.sections.last_mut() {
+						section.exit = Some(code);
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Hook output by group and stage, streamed while set runs
+pub fn hook_log(app: &mut TemplateApp, ui: &mut Ui) {
+	let log = &mut app.hook_log;
+	log.poll();
+	if log.sections.is_empty() {
+		return;
+	}
+
+	egui::ScrollArea::vertical()
+		.id_source("hook_log")
+		.max_height(200.0)
+		.stick_to_bottom(true)
+		.show(ui, |ui| {
+			ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 3.0);
+			for section in &log.sections {
+				ui.strong(format!("{} · {} · {}", section.group, section.stage, section.hook));
+				for (line, stderr) in &section.lines {
+					let text = RichText::new(line).monospace();
+					match stderr {
+						true => ui.label(text.color(Color32::LIGHT_RED)),
+						false => ui.label(text),
+					};
+				}
+
+				match section.exit {
+					None => ui.horizontal(|ui| {
+						ui.add(egui::Spinner::new());
+						ui.label("running…");
+					}),
+					Some(Some(0)) => ui.horizontal(|ui| {
+						ui.colored_label(Color32::LIGHT_GREEN, "exited with 0");
+					}),
+					Some(Some(code)) => ui.horizontal(|ui| {
+						ui.colored_label(Color32::LIGHT_RED, format!("exited with {code}"));
+					}),
+					Some(None) => ui.horizontal(|ui| {
+						ui.colored_label(Color32::LIGHT_RED, "killed");
+					}),
+				};
+				ui.separator();
+			}
+		});
+}
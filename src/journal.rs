@@ -0,0 +1,411 @@
+use crate::app::TemplateApp;
+use crate::format::format_time;
+use crate::groups::{configs_dir, copy_path, home_dir, load_groups, move_path, symlink, target_path, walk_files};
+use crate::plan::{plan_add, Action};
+use crate::worker::Job;
+use egui::{Align2, Button, Ui};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tuckr::Cli;
+
+/// Only the latest operations can be undone
+const MAX_JOURNAL: usize = 50;
+
+/// A change a command made on disk
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub enum Change {
+	/// a symlink at `target` pointing to `source` was created
+	Linked { target: PathBuf, source: PathBuf },
+	/// the symlink at `target` pointing to `source` was removed
+	Unlinked { target: PathBuf, source: PathBuf },
+	/// a file was copied into the repo
+	Created { path: PathBuf },
+	/// a file was deleted, a copy was kept at `backup`
+	Deleted { path: PathBuf, backup: PathBuf },
+	/// a file was moved out of the way of a link
+	Moved { from: PathBuf, to: PathBuf },
+}
+
+impl Change {
+	/// What undoing the change does
+	fn describe(&self) -> String {
+		match self {
+			Change::Linked { target, .. } => format!("unlink {}", target.display()),
+			Change::Unlinked { target, source } => format!("relink {} → {}", target.display(), source.display()),
+			Change::Created { path } => format!("delete {}", path.display()),
+			Change::Deleted { path, .. } => format!("restore {}", path.display()),
+			Change::Moved { from, to } => format!("move {} back to {}", to.display(), from.display()),
+		}
+	}
+
+	fn undo(&self) -> io::Result<()> {
+		match self {
+			Change::Linked { target, source } => match fs::read_link(target) {
+				// something else was put there since
+				Ok(link) if &link == source => fs::remove_file(target).or_else(|_| fs::remove_dir(target)),
+				_ => Ok(()),
+			},
+			Change::Unlinked { target, source } => {
+				if let Some(parent) = target.parent() {
+					fs::create_dir_all(parent)?;
+				}
+				symlink(source, target)
+			}
+			Change::Created { path } => fs::remove_file(path),
+			Change::Deleted { path, backup } => copy_path(backup, path),
+			Change::Moved { from, to } => {
+				if let Some(parent) = from.parent() {
+					fs::create_dir_all(parent)?;
+				}
+				move_path(to, from)
+			}
+		}
+	}
+}
+
+/// Everything a command changed, undone as a whole
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct Operation {
+	pub command: String,
+	pub time: SystemTime,
+	pub changes: Vec<Change>,
+	/// copies made for this operation, deleted with it
+	#[serde(default)]
+	pub backup: Option<PathBuf>,
+}
+
+/// Links and repo files of the groups a command touches, taken before it runs
+pub struct Snapshot {
+	group_dirs: Vec<PathBuf>,
+	configs_dir: PathBuf,
+	links: BTreeMap<PathBuf, PathBuf>,
+	files: BTreeSet<PathBuf>,
+	/// copy of the groups for commands that delete them, laid out like `Configs/`
+	groups_copy: Option<PathBuf>,
+	/// where files the command removes are kept
+	dir: Option<PathBuf>,
+	kept: usize,
+	/// changes made before the command ran, like clearing conflicts
+	changes: Vec<Change>,
+	/// (target, changes) that only happened if the command linked the target
+	replaced: Vec<(PathBuf, Vec<Change>)>,
+}
+
+impl Snapshot {
+	/// None for commands that aren't journaled
+	pub fn take(cli: &Cli) -> Option<Self> {
+		let (groups, exclude) = match cli {
+			Cli::Add { groups, exclude, .. } | Cli::Set { groups, exclude, .. } | Cli::Rm { groups, exclude } => {
+				(groups.as_slice(), exclude.as_slice())
+			}
+			Cli::Push { group, .. } => (std::slice::from_ref(group), &[][..]),
+			Cli::Pop { groups } => (groups.as_slice(), &[][..]),
+			_ => return None,
+		};
+		let mut snapshot = Self::of(groups, exclude)?;
+		match cli {
+			Cli::Add {
+				groups,
+				exclude,
+				force,
+				adopt,
+			} => snapshot.keep_replaced(groups, exclude, *force, *adopt),
+			Cli::Push { group, files } => snapshot.keep_pushed(group, files),
+			_ => (),
+		}
+		Some(snapshot)
+	}
+
+	/// Copy the targets and dotfiles add or set with `force` or `adopt` will replace,
+	/// they're recorded once the command linked the target
+	pub fn keep_replaced(&mut self, groups: &[String], exclude: &[String], force: bool, adopt: bool) {
+		if !force && !adopt {
+			return;
+		}
+		for link in plan_add(groups, exclude, force, adopt) {
+			let mut changes = Vec::new();
+			match link.action {
+				Action::Overwrite => match self.keep(&link.target) {
+					Ok(backup) => changes.push(Change::Deleted {
+						path: link.target.clone(),
+						backup,
+					}),
+					Err(_) => continue,
+				},
+				Action::Adopt => {
+					match self.keep(&link.path) {
+						Ok(backup) => changes.push(Change::Deleted {
+							path: link.path.clone(),
+							backup,
+						}),
+						Err(_) => continue,
+					}
+					changes.push(Change::Moved {
+						from: link.target.clone(),
+						to: link.path,
+					});
+				}
+				_ => continue,
+			}
+			self.replaced.push((link.target, changes));
+		}
+	}
+
+	/// Copy the files of `group` push will overwrite
+	fn keep_pushed(&mut self, group: &str, files: &[String]) {
+		let Some(home) = home_dir() else {
+			return;
+		};
+		for file in files {
+			let Ok(relative) = Path::new(file).strip_prefix(&home) else {
+				continue;
+			};
+			let path = self.configs_dir.join(group).join(relative);
+			if path.symlink_metadata().is_err() {
+				continue;
+			}
+			if let Ok(backup) = self.keep(&path) {
+				self.record(Change::Deleted { path, backup });
+			}
+		}
+	}
+
+	/// Deleted repo files can be restored from `copy`, the archive for pop
+	pub fn groups_copy(&mut self, copy: PathBuf) {
+		self.groups_copy = Some(copy);
+	}
+
+	/// Snapshot of `groups` without `exclude`, `\*` is all groups
+	pub fn of(groups: &[String], exclude: &[String]) -> Option<Self> {
+		let groups = match groups.iter().any(|g| g == r"\*") {
+			true => load_groups().ok()?,
+			false => groups.to_vec(),
+		};
+		let configs_dir = configs_dir().ok()?;
+		let group_dirs: Vec<_> = groups
+			.iter()
+			.filter(|g| !exclude.contains(g))
+			.map(|g| configs_dir.join(g))
+			.collect();
+
+		let stamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |d| d.as_millis());
+		let (links, files) = scan(&group_dirs);
+		Some(Self {
+			group_dirs,
+			configs_dir,
+			links,
+			files,
+			groups_copy: None,
+			dir: backups_dir().map(|d| d.join(stamp.to_string())),
+			kept: 0,
+			changes: Vec::new(),
+			replaced: Vec::new(),
+		})
+	}
+
+	/// Copy `path` before it's removed, returns where the copy is
+	pub fn keep(&mut self, path: &Path) -> io::Result<PathBuf> {
+		let dir = self
+			.dir
+			.as_ref()
+			.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no directory for backups"))?;
+		self.kept += 1;
+		let copy = dir
+			.join("kept")
+			.join(self.kept.to_string())
+			.join(path.file_name().unwrap_or_default());
+		copy_path(path, &copy)?;
+		Ok(copy)
+	}
+
+	/// A change made outside of the command, undone after the command's own
+	pub fn record(&mut self, change: Change) {
+		self.changes.push(change);
+	}
+
+	/// Compare with the disk after the command ran
+	pub fn finish(self, command: String) -> Operation {
+		let (links, files) = scan(&self.group_dirs);
+		let mut changes = self.changes;
+		for (target, replaced) in self.replaced {
+			if fs::read_link(&target).is_ok() {
+				changes.extend(replaced);
+			}
+		}
+
+		for (target, source) in &links {
+			if self.links.get(target) != Some(source) {
+				changes.push(Change::Linked {
+					target: target.clone(),
+					source: source.clone(),
+				});
+			}
+		}
+		// links of deleted files aren't found by the scan, so look at the links themselves
+		for (target, source) in self.links {
+			if fs::read_link(&target).ok().as_ref() != Some(&source) {
+				changes.push(Change::Unlinked { target, source });
+			}
+		}
+		for path in files.difference(&self.files) {
+			changes.push(Change::Created { path: path.clone() });
+		}
+		for path in self.files.difference(&files) {
+			let backup = match (&self.groups_copy, path.strip_prefix(&self.configs_dir)) {
+				(Some(backup), Ok(relative)) => backup.join(relative),
+				_ => continue,
+			};
+			changes.push(Change::Deleted {
+				path: path.clone(),
+				backup,
+			});
+		}
+
+		Operation {
+			command,
+			time: SystemTime::now(),
+			changes,
+			backup: self.dir.filter(|d| d.exists()),
+		}
+	}
+}
+
+/// (target → link, repo files) of every file and directory in `group_dirs`
+fn scan(group_dirs: &[PathBuf]) -> (BTreeMap<PathBuf, PathBuf>, BTreeSet<PathBuf>) {
+	let mut links = BTreeMap::new();
+	let mut files = BTreeSet::new();
+	for group_dir in group_dirs {
+		for path in walk_files(group_dir) {
+			// directories can be linked as a whole too
+			for dir in path
+				.ancestors()
+				.skip(1)
+				.take_while(|d| d.starts_with(group_dir) && *d != group_dir)
+			{
+				if let Some(target) = target_path(group_dir, dir) {
+					if let Ok(link) = fs::read_link(&target) {
+						links.insert(target, link);
+					}
+				}
+			}
+			if let Some(target) = target_path(group_dir, &path) {
+				if let Ok(link) = fs::read_link(&target) {
+					links.insert(target, link);
+				}
+			}
+			files.insert(path);
+		}
+	}
+	(links, files)
+}
+
+/// Where copies of removed files are kept until their operation is undone or trimmed
+fn backups_dir() -> Option<PathBuf> {
+	let cache = std::env::var_os("XDG_CACHE_HOME")
+		.or_else(|| std::env::var_os("LOCALAPPDATA"))
+		.map(PathBuf::from)
+		.or_else(|| home_dir().map(|h| h.join(".cache")))?;
+	Some(cache.join("tuckr-ui").join("undo"))
+}
+
+/// Keep a finished operation so it can be undone
+pub fn journal(app: &mut TemplateApp, operation: Operation) {
+	if operation.changes.is_empty() {
+		return;
+	}
+	app.journal.push(operation);
+	if app.journal.len() > MAX_JOURNAL {
+		let trimmed = app.journal.remove(0);
+		if let Some(backup) = trimmed.backup {
+			let _ = fs::remove_dir_all(backup);
+		}
+	}
+}
+
+/// Revert the changes newest first, returns what was done
+fn undo(operation: &Operation) -> (String, ExitCode) {
+	let mut output = String::new();
+	let mut code = ExitCode::SUCCESS;
+	for change in operation.changes.iter().rev() {
+		match change.undo() {
+			Ok(()) => output.push_str(&change.describe()),
+			Err(e) => {
+				output.push_str(&format!("couldn't {}: {e}", change.describe()));
+				code = ExitCode::FAILURE;
+			}
+		}
+		output.push('\n');
+	}
+	// a failed undo might be finished by hand with the copies
+	if let (ExitCode::SUCCESS, Some(backup)) = (code, &operation.backup) {
+		let _ = fs::remove_dir_all(backup);
+	}
+	(output, code)
+}
+
+/// Undo button for the last operation
+pub fn undo_button(app: &mut TemplateApp, ui: &mut Ui) {
+	let last = app.journal.last().map(|op| op.command.clone());
+	let button = ui.add_enabled(last.is_some(), Button::new("Undo"));
+	if let Some(command) = last {
+		if button.on_hover_text(format!("undo {command}")).clicked() {
+			app.undo_preview = true;
+		}
+	}
+}
+
+/// What undoing the last operation restores, confirmed before anything is changed
+pub fn undo_window(app: &mut TemplateApp, ctx: &egui::Context) {
+	if !app.undo_preview {
+		return;
+	}
+	let Some(operation) = app.journal.last() else {
+		app.undo_preview = false;
+		return;
+	};
+
+	let mut open = true;
+	let mut confirm = false;
+	egui::Window::new(format!("Undo {}", operation.command))
+		.collapsible(false)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.open(&mut open)
+		.show(ctx, |ui| {
+			ui.style_mut().spacing.item_spacing = egui::vec2(10.0, 10.0);
+			ui.label(format!(
+				"{} run at {} made {} changes:",
+				operation.command,
+				format_time(operation.time),
+				operation.changes.len()
+			));
+
+			egui::ScrollArea::vertical()
+				.id_source("undo_preview")
+				.max_height(250.0)
+				.show(ui, |ui| {
+					ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+					for change in operation.changes.iter().rev() {
+						ui.label(change.describe());
+					}
+				});
+
+			confirm = ui.button("Undo").clicked();
+		});
+
+	if confirm {
+		if let Some(operation) = app.journal.pop() {
+			app.job = Some(Job::spawn(ctx, format!("Undo {}", operation.command), move || {
+				undo(&operation)
+			}));
+		}
+	}
+	if confirm || !open {
+		app.undo_preview = false;
+	}
+}
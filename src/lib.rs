@@ -2,6 +2,26 @@
 
 mod app;
 pub use app::TemplateApp;
+pub(crate) mod archive;
 pub(crate) mod cmd;
+pub(crate) mod conflicts;
+pub(crate) mod diff;
+pub(crate) mod editor;
+pub(crate) mod error;
 pub(crate) mod filepicker;
+pub(crate) mod format;
 pub(crate) mod groups;
+pub(crate) mod history;
+pub(crate) mod hooks;
+pub(crate) mod journal;
+pub(crate) mod loader;
+pub(crate) mod lookup;
+pub(crate) mod plan;
+pub(crate) mod profiles;
+pub(crate) mod reorganize;
+pub(crate) mod secrets;
+pub(crate) mod status;
+pub(crate) mod stow;
+pub(crate) mod tree;
+pub(crate) mod watcher;
+pub(crate) mod worker;
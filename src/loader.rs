@@ -0,0 +1,73 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+type Load<T> = Box<dyn FnOnce() -> T + Send>;
+
+/// Reads the disk on a background thread so a frame never waits on it
+pub struct Loader<T> {
+	requests: Sender<(u64, egui::Context, Load<T>)>,
+	results: Receiver<(u64, T)>,
+	/// the last load asked for
+	requested: u64,
+	/// the load the last result was for
+	loaded: u64,
+}
+
+impl<T: Send + 'static> Default for Loader<T> {
+	fn default() -> Self {
+		let (requests, request_rx) = mpsc::channel::<(u64, egui::Context, Load<T>)>();
+		let (result_tx, results) = mpsc::channel();
+		thread::spawn(move || {
+			while let Ok(mut request) = request_rx.recv() {
+				// only the newest of the loads asked for while busy is run
+				if let Some(latest) = request_rx.try_iter().last() {
+					request = latest;
+				}
+				let (id, ctx, load) = request;
+
+				if result_tx.send((id, load())).is_err() {
+					return;
+				}
+				ctx.request_repaint();
+			}
+		});
+
+		Self {
+			requests,
+			results,
+			requested: 0,
+			loaded: 0,
+		}
+	}
+}
+
+impl<T> Loader<T> {
+	/// Run `load` in the background, its result replaces older ones
+	pub fn load(&mut self, ctx: &egui::Context, load: impl FnOnce() -> T + Send + 'static) {
+		self.requested += 1;
+		let _ = self.requests.send((self.requested, ctx.clone(), Box::new(load)));
+	}
+
+	/// True until the latest load came back
+	pub fn loading(&self) -> bool {
+		self.loaded < self.requested
+	}
+
+	/// A spinner with `label` until the latest load came back
+	pub fn spinner(&self, ui: &mut egui::Ui, label: &str) {
+		if self.loading() {
+			ui.horizontal(|ui| {
+				ui.spinner();
+				ui.label(label);
+			});
+		}
+	}
+
+	/// The result of the latest load once it came back, results of older loads are dropped
+	pub fn poll(&mut self) -> Option<T> {
+		let requested = self.requested;
+		let (id, result) = self.results.try_iter().filter(|(id, _)| *id == requested).last()?;
+		self.loaded = id;
+		Some(result)
+	}
+}
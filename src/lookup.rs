@@ -0,0 +1,131 @@
+use crate::app::{Page, TemplateApp};
+use crate::cmd::groupis_cmd;
+use crate::filepicker::lookup_file_picker;
+use crate::loader::Loader;
+use egui::{Color32, Ui};
+
+/// (file, groups) of every looked up file
+type Found = Vec<(String, Vec<String>)>;
+
+/// Groups of the looked up files, found in the background
+#[derive(Default)]
+pub struct GroupLookup {
+	found: Found,
+	/// the files the last lookup was for
+	looked_up: Vec<String>,
+	loader: Loader<Found>,
+}
+
+/// `text` without the escape codes tuckr colors its output with
+fn strip_ansi(text: &str) -> String {
+	let mut plain = String::with_capacity(text.len());
+	let mut chars = text.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			// codes end with a letter, like `\x1b[32m`
+			'\u{1b}' => {
+				for c in chars.by_ref() {
+					if c.is_ascii_alphabetic() {
+						break;
+					}
+				}
+			}
+			c => plain.push(c),
+		}
+	}
+	plain
+}
+
+/// The known `groups` named in the output of groupis
+fn owning(output: &str, groups: &[String]) -> Vec<String> {
+	let output = strip_ansi(output);
+	let words: Vec<_> = output
+		.split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+		.collect();
+	groups.iter().filter(|g| words.contains(&g.as_str())).cloned().collect()
+}
+
+/// Groups tuckr's groupis says `file` belongs to
+fn groups_owning(file: String, groups: &[String]) -> Vec<String> {
+	let (output, _) = groupis_cmd(&[file]);
+	owning(&output, groups)
+}
+
+/// Find the groups picked or dropped files belong to
+pub fn group_lookup(app: &mut TemplateApp, ui: &mut Ui) {
+	lookup_file_picker(app, ui);
+
+	let files = app.lookup_files.get_or_insert_with(Vec::new);
+	let lookup = &mut app.lookup;
+	if lookup.looked_up != *files {
+		lookup.found.retain(|(file, _)| files.contains(file));
+		let looking_up = files.clone();
+		let groups = app.found_groups.clone().unwrap_or_default();
+		lookup.loader.load(ui.ctx(), move || {
+			looking_up
+				.into_iter()
+				.map(|file| (file.clone(), groups_owning(file, &groups)))
+				.collect()
+		});
+		lookup.looked_up.clone_from(files);
+	}
+	if let Some(found) = lookup.loader.poll() {
+		lookup.found = found;
+	}
+	lookup.loader.spinner(ui, "looking up…");
+
+	let mut remove = None;
+	let mut select = None;
+	egui::ScrollArea::vertical()
+		.id_source("group_lookup")
+		.max_height(200.0)
+		.show(ui, |ui| {
+			egui::Grid::new("group_lookup_grid").striped(true).show(ui, |ui| {
+				for (i, (file, groups)) in lookup.found.iter().enumerate() {
+					if ui.small_button("🗙").on_hover_text("remove").clicked() {
+						remove = Some(i);
+					}
+					ui.label(file);
+					ui.horizontal(|ui| {
+						if groups.is_empty() {
+							ui.colored_label(Color32::GRAY, "no group");
+						}
+						for group in groups {
+							if ui.button(group).on_hover_text("select this group").clicked() {
+								select = Some(group.clone());
+							}
+						}
+					});
+					ui.end_row();
+				}
+			});
+		});
+
+	if let Some(i) = remove {
+		let (file, _) = lookup.found.remove(i);
+		files.retain(|f| *f != file);
+		app.page = Page::GroupIs(app.lookup_files.clone());
+	}
+	if let Some(group) = select {
+		app.groups = Some(vec![group]);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn strips_colors() {
+		assert_eq!(strip_ansi("\u{1b}[32mnvim\u{1b}[0m"), "nvim");
+		assert_eq!(strip_ansi("plain"), "plain");
+	}
+
+	#[test]
+	fn finds_known_groups() {
+		let groups = vec!["nvim".to_string(), "zsh".to_string(), "zsh_linux".to_string()];
+		assert_eq!(owning("\u{1b}[1mzsh_linux\u{1b}[0m\n", &groups), ["zsh_linux"]);
+		assert_eq!(owning("nvim, zsh", &groups), ["nvim", "zsh"]);
+		assert!(owning("no group found", &groups).is_empty());
+	}
+}
@@ -1,9 +1,29 @@
 #![warn(clippy::all, rust_2018_idioms, future_incompatible)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 mod app;
+mod archive;
 mod cmd;
+mod conflicts;
+mod diff;
+mod editor;
+mod error;
 mod filepicker;
+mod format;
 mod groups;
+mod history;
+mod hooks;
+mod journal;
+mod loader;
+mod lookup;
+mod plan;
+mod profiles;
+mod reorganize;
+mod secrets;
+mod status;
+mod stow;
+mod tree;
+mod watcher;
+mod worker;
 
 // Only compile natively:
 fn main() -> eframe::Result {
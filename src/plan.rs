@@ -0,0 +1,143 @@
+use crate::app::TemplateApp;
+use crate::loader::Loader;
+use crate::status::{load_status, LinkState};
+use egui::{Color32, RichText, Ui};
+use std::path::PathBuf;
+
+/// What add would do with one dotfile
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+	/// create the symlink
+	Link,
+	/// the symlink is already there
+	Linked,
+	/// move the existing target into the group, then link it
+	Adopt,
+	/// delete the existing target, then link it
+	Overwrite,
+	/// the target exists and neither force nor adopt are set
+	Conflict,
+}
+
+impl Action {
+	fn text(self) -> RichText {
+		let (text, color) = match self {
+			Action::Link => ("link", Color32::LIGHT_GREEN),
+			Action::Linked => ("linked", Color32::GRAY),
+			Action::Adopt => ("adopt", Color32::LIGHT_BLUE),
+			Action::Overwrite => ("overwrite", Color32::from_rgb(230, 160, 60)),
+			Action::Conflict => ("conflict", Color32::LIGHT_RED),
+		};
+		RichText::new(text).color(color)
+	}
+}
+
+pub struct PlannedLink {
+	pub group: String,
+	pub path: PathBuf,
+	pub target: PathBuf,
+	pub action: Action,
+}
+
+/// The symlinks add would create for `groups`, nothing on disk is changed
+pub fn plan_add(groups: &[String], exclude: &[String], force: bool, adopt: bool) -> Vec<PlannedLink> {
+	load_status(groups)
+		.into_iter()
+		.filter(|g| !exclude.contains(&g.group) && g.state != LinkState::Incompatible)
+		.flat_map(|g| {
+			let group = g.group;
+			g.files.into_iter().map(move |f| PlannedLink {
+				group: group.clone(),
+				action: match f.state {
+					LinkState::Symlinked => Action::Linked,
+					LinkState::Conflicting if adopt => Action::Adopt,
+					LinkState::Conflicting if force => Action::Overwrite,
+					LinkState::Conflicting => Action::Conflict,
+					_ => Action::Link,
+				},
+				path: f.path,
+				target: f.target,
+			})
+		})
+		.collect()
+}
+
+/// (groups, exclude, force, adopt) a plan was made for
+type PlanInputs = (Vec<String>, Vec<String>, bool, bool);
+
+/// Dry run of the add page
+#[derive(Default)]
+pub struct AddPreview {
+	/// None until preview is clicked
+	plan: Option<Vec<PlannedLink>>,
+	inputs: Option<PlanInputs>,
+	loader: Loader<Vec<PlannedLink>>,
+}
+
+impl AddPreview {
+	/// Plan again on the next frame if a plan is shown
+	pub fn reload(&mut self) {
+		self.inputs = None;
+	}
+}
+
+/// Preview button and the planned symlinks of add
+pub fn add_preview(app: &mut TemplateApp, ui: &mut Ui, exclude: &[String], force: bool, adopt: bool) {
+	let inputs = (
+		app.groups.clone().unwrap_or(vec![r"\*".into()]),
+		exclude.to_vec(),
+		force,
+		adopt,
+	);
+	let preview = &mut app.add_preview;
+
+	// clicking again plans again, the disk may have changed since
+	if ui.button("Preview").clicked() {
+		preview.plan.get_or_insert_with(Vec::new);
+		preview.inputs = None;
+	}
+	// keep the plan up to date with the selected groups and flags
+	if preview.plan.is_some() && preview.inputs.as_ref() != Some(&inputs) {
+		let (groups, exclude, force, adopt) = inputs.clone();
+		preview
+			.loader
+			.load(ui.ctx(), move || plan_add(&groups, &exclude, force, adopt));
+		preview.inputs = Some(inputs);
+	}
+	if let Some(plan) = preview.loader.poll() {
+		preview.plan = Some(plan);
+	}
+	preview.loader.spinner(ui, "planning…");
+
+	let Some(plan) = &preview.plan else {
+		return;
+	};
+
+	let count = |action| plan.iter().filter(|p| p.action == action).count();
+	ui.label(format!(
+		"{} to link, {} already linked, {} to adopt, {} to overwrite, {} conflicting",
+		count(Action::Link),
+		count(Action::Linked),
+		count(Action::Adopt),
+		count(Action::Overwrite),
+		count(Action::Conflict),
+	));
+
+	egui::ScrollArea::vertical()
+		.id_source("add_preview")
+		.max_height(200.0)
+		.show(ui, |ui| {
+			egui::Grid::new("add_preview_grid")
+				.striped(true)
+				.num_columns(3)
+				.show(ui, |ui| {
+					for link in plan {
+						ui.label(link.action.text());
+						ui.label(&link.group);
+						ui.label(link.target.display().to_string())
+							.on_hover_text(format!("→ {}", link.path.display()));
+						ui.end_row();
+					}
+				});
+		});
+}
@@ -0,0 +1,115 @@
+use crate::app::{Page, TemplateApp};
+use egui::{Button, TextEdit, Ui};
+
+/// Groups, excludes and flags saved under a name like "laptop" or "work"
+#[derive(serde::Deserialize, serde::Serialize, Clone, Default)]
+pub struct Profile {
+	pub name: String,
+	pub groups: Vec<String>,
+	pub exclude: Vec<String>,
+	pub force: bool,
+	pub adopt: bool,
+}
+
+impl Profile {
+	/// The current selection saved as `name`
+	fn from_app(app: &TemplateApp, name: String) -> Self {
+		Self {
+			name,
+			groups: app.groups.clone().unwrap_or_default(),
+			exclude: app.exclude.clone().unwrap_or_default(),
+			force: app.force,
+			adopt: app.adopt,
+		}
+	}
+}
+
+/// Select the groups, excludes and flags of the profile called `name`
+pub fn switch_profile(app: &mut TemplateApp, name: &str) {
+	let Some(profile) = app.profiles.iter().find(|p| p.name == name) else {
+		app.profile = None;
+		return;
+	};
+
+	app.groups = Some(profile.groups.clone());
+	app.exclude = Some(profile.exclude.clone());
+	app.force = profile.force;
+	app.adopt = profile.adopt;
+	app.profile = Some(profile.name.clone());
+}
+
+/// Save the current selection, replacing a profile with the same name
+fn save_profile(app: &mut TemplateApp, name: String) {
+	let profile = Profile::from_app(app, name);
+	match app.profiles.iter_mut().find(|p| p.name == profile.name) {
+		Some(saved) => *saved = profile.clone(),
+		None => app.profiles.push(profile.clone()),
+	}
+	app.profile = Some(profile.name);
+}
+
+/// Set the groups of a profile in one go
+fn apply_profile(app: &mut TemplateApp, ctx: &egui::Context, name: &str) {
+	switch_profile(app, name);
+	app.page = Page::Set(app.exclude.clone(), app.force, app.adopt);
+
+	match app.page.clone().into_cli(app.groups.clone().unwrap_or_default()) {
+		Ok(cli) => app.start(ctx, cli),
+		Err(e) => app.output = e,
+	}
+}
+
+/// Dropdown to switch between profiles, with buttons to apply, update, delete and save them
+pub fn profile_bar(app: &mut TemplateApp, ui: &mut Ui) {
+	ui.horizontal(|ui| {
+		ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+
+		let mut switch_to = None;
+		egui::ComboBox::from_id_source("profiles")
+			.selected_text(app.profile.as_deref().unwrap_or("No profile"))
+			.show_ui(ui, |ui| {
+				for profile in &app.profiles {
+					let selected = app.profile.as_ref() == Some(&profile.name);
+					if ui.selectable_label(selected, &profile.name).clicked() {
+						switch_to = Some(profile.name.clone());
+					}
+				}
+			});
+		if let Some(name) = switch_to {
+			switch_profile(app, &name);
+		}
+
+		if let Some(name) = app.profile.clone() {
+			if ui
+				.button("Apply")
+				.on_hover_text("set the groups of this profile")
+				.clicked()
+			{
+				apply_profile(app, ui.ctx(), &name);
+			}
+			if ui
+				.button("Update")
+				.on_hover_text("save the current selection")
+				.clicked()
+			{
+				save_profile(app, name.clone());
+			}
+			if ui.button("🗑").on_hover_text("delete this profile").clicked() {
+				app.profiles.retain(|p| p.name != name);
+				app.profile = None;
+			}
+		}
+
+		ui.add_space(10.0);
+		ui.add(
+			TextEdit::singleline(&mut app.profile_name)
+				.hint_text("new profile")
+				.desired_width(100.0),
+		);
+		let name = app.profile_name.trim().to_string();
+		if ui.add_enabled(!name.is_empty(), Button::new("Save")).clicked() {
+			save_profile(app, name);
+			app.profile_name.clear();
+		}
+	});
+}
@@ -0,0 +1,194 @@
+use crate::groups::{dotfiles_dir, is_valid_target, symlink, target_path, walk_files};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Every part of the dotfiles a group has a directory in
+const TREES: [&str; 3] = ["Configs", "Hooks", "Secrets"];
+
+/// If `target` is a symlink to `path` itself, not to one of its directories
+fn links_to(target: &Path, path: &Path) -> bool {
+	fs::read_link(target).is_ok()
+		&& matches!((fs::canonicalize(target), fs::canonicalize(path)), (Ok(t), Ok(p)) if t == p)
+}
+
+/// (target, path relative to the group) of every symlink in the home directory into `group_dir`
+fn links_into(group_dir: &Path) -> BTreeMap<PathBuf, PathBuf> {
+	let mut links = BTreeMap::new();
+	for file in walk_files(group_dir) {
+		// directories can be linked as a whole too
+		for path in file
+			.ancestors()
+			.take_while(|p| p.starts_with(group_dir) && *p != group_dir)
+		{
+			let Some(target) = target_path(group_dir, path) else {
+				continue;
+			};
+			if links_to(&target, path) {
+				if let Ok(relative) = path.strip_prefix(group_dir) {
+					links.insert(target, relative.to_path_buf());
+				}
+			}
+		}
+	}
+	links
+}
+
+/// Point the link at `target` to `source`, or just remove it if `source` can't be deployed here
+fn relink(target: &Path, source: &Path, deploy: bool) -> String {
+	let done = fs::remove_file(target)
+		.or_else(|_| fs::remove_dir(target))
+		.and_then(|()| if deploy { symlink(source, target) } else { Ok(()) });
+	match (done, deploy) {
+		(Ok(()), true) => format!("relinked {}\n", target.display()),
+		(Ok(()), false) => format!("unlinked {}, the group is for another OS\n", target.display()),
+		(Err(e), _) => format!("couldn't relink {}: {e}\n", target.display()),
+	}
+}
+
+/// Replace the link at `target` to `dir` with a real directory of links to each file in it
+fn unfold(target: &Path, dir: &Path) -> io::Result<()> {
+	fs::remove_file(target).or_else(|_| fs::remove_dir(target))?;
+	fs::create_dir_all(target)?;
+	for file in walk_files(dir) {
+		let link = target.join(file.strip_prefix(dir).unwrap_or(&file));
+		if let Some(parent) = link.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		symlink(&file, &link)?;
+	}
+	Ok(())
+}
+
+/// Rename `old` in `Configs/`, `Hooks/` and `Secrets/` and move its links to the new name
+pub fn rename_group(old: &str, new: &str) -> (String, ExitCode) {
+	let dotfiles = match dotfiles_dir() {
+		Ok(p) => p,
+		Err(e) => return (e.to_string(), ExitCode::FAILURE),
+	};
+
+	// check everything first so the group isn't left half renamed
+	let mut renames = Vec::new();
+	for tree in TREES {
+		let from = dotfiles.join(tree).join(old);
+		let to = dotfiles.join(tree).join(new);
+		if from.symlink_metadata().is_err() {
+			continue;
+		}
+		if to.symlink_metadata().is_ok() {
+			return (format!("{} already exists", to.display()), ExitCode::FAILURE);
+		}
+		renames.push((from, to));
+	}
+	if renames.is_empty() {
+		return (format!("{old} doesn't exist"), ExitCode::FAILURE);
+	}
+
+	let old_dir = dotfiles.join("Configs").join(old);
+	let new_dir = dotfiles.join("Configs").join(new);
+	let links = links_into(&old_dir);
+
+	let mut output = String::new();
+	for (i, (from, to)) in renames.iter().enumerate() {
+		if let Err(e) = fs::rename(from, to) {
+			output.push_str(&format!("couldn't rename {}: {e}\n", from.display()));
+			// put back what was already renamed
+			for (from, to) in renames[..i].iter().rev() {
+				match fs::rename(to, from) {
+					Ok(()) => output.push_str(&format!("renamed {} back\n", from.display())),
+					Err(e) => output.push_str(&format!("couldn't rename {} back: {e}\n", to.display())),
+				}
+			}
+			return (output, ExitCode::FAILURE);
+		}
+		output.push_str(&format!("renamed {} to {}\n", from.display(), to.display()));
+	}
+
+	let deploy = is_valid_target(new);
+	for (target, relative) in links {
+		output.push_str(&relink(&target, &new_dir.join(relative), deploy));
+	}
+	(output, ExitCode::SUCCESS)
+}
+
+/// Move a file of one group to the same place in `to`, the file stays linked if it was
+pub fn move_file(path: &Path, to: &str) -> (String, ExitCode) {
+	let dotfiles = match dotfiles_dir() {
+		Ok(p) => p,
+		Err(e) => return (e.to_string(), ExitCode::FAILURE),
+	};
+	// <tree>/<group>/<file>
+	let mut parts = match path.strip_prefix(&dotfiles) {
+		Ok(relative) => relative.components(),
+		Err(_) => return (format!("{} isn't in the dotfiles", path.display()), ExitCode::FAILURE),
+	};
+	let (Some(tree), Some(group)) = (parts.next(), parts.next()) else {
+		return (format!("{} isn't in a group", path.display()), ExitCode::FAILURE);
+	};
+	let from_dir = dotfiles.join(tree).join(group);
+	let to_dir = dotfiles.join(tree).join(to);
+	let destination = to_dir.join(parts.as_path());
+	if destination.symlink_metadata().is_ok() {
+		return (format!("{} already exists", destination.display()), ExitCode::FAILURE);
+	}
+
+	// the file might be deployed through a link to one of its directories
+	let linked = path
+		.ancestors()
+		.take_while(|p| p.starts_with(&from_dir) && *p != from_dir)
+		.find_map(|p| {
+			target_path(&from_dir, p)
+				.filter(|t| links_to(t, p))
+				.map(|t| (t, p.to_path_buf()))
+		});
+
+	let moved = match destination.parent() {
+		Some(parent) => fs::create_dir_all(parent).and_then(|()| fs::rename(path, &destination)),
+		None => fs::rename(path, &destination),
+	};
+	if let Err(e) = moved {
+		return (format!("couldn't move {}: {e}", path.display()), ExitCode::FAILURE);
+	}
+	let mut output = format!("moved {} to {}\n", path.display(), destination.display());
+
+	// don't leave empty directories behind
+	for dir in path
+		.ancestors()
+		.skip(1)
+		.take_while(|d| d.starts_with(&from_dir) && *d != from_dir)
+	{
+		if fs::remove_dir(dir).is_err() {
+			break;
+		}
+	}
+
+	match linked {
+		Some((target, linked)) if linked == path => {
+			output.push_str(&relink(&target, &destination, is_valid_target(to)));
+		}
+		// the directory stays with the old group, so link its files one by one and the moved file on its own
+		Some((target, linked)) => {
+			match unfold(&target, &linked) {
+				Ok(()) => output.push_str(&format!("unfolded {}\n", target.display())),
+				Err(e) => {
+					output.push_str(&format!("couldn't unfold {}: {e}\n", target.display()));
+					return (output, ExitCode::FAILURE);
+				}
+			}
+			if let Some(target) = target_path(&to_dir, &destination).filter(|_| is_valid_target(to)) {
+				let linked = match target.parent() {
+					Some(parent) => fs::create_dir_all(parent).and_then(|()| symlink(&destination, &target)),
+					None => symlink(&destination, &target),
+				};
+				match linked {
+					Ok(()) => output.push_str(&format!("linked {}\n", target.display())),
+					Err(e) => output.push_str(&format!("couldn't link {}: {e}\n", target.display())),
+				}
+			}
+		}
+		None => (),
+	}
+	(output, ExitCode::SUCCESS)
+}
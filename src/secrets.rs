@@ -0,0 +1,226 @@
+use crate::app::TemplateApp;
+use crate::cmd::{decrypt_cmd_with_password, run_with_passphrase};
+use crate::format::{format_size, format_time};
+use crate::groups::{dotfiles_dir, target_path, walk_files};
+use crate::history::HistoryEntry;
+use crate::loader::Loader;
+use crate::worker::Job;
+use egui::{Align2, Button, Color32, Key, TextEdit, Ui};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::SystemTime;
+use tuckr::Cli;
+
+/// An encrypted file in `Secrets/`
+pub struct SecretFile {
+	pub group: String,
+	pub path: PathBuf,
+	/// where the secret is restored to when decrypted
+	pub target: PathBuf,
+	pub size: u64,
+	pub modified: Option<SystemTime>,
+}
+
+/// Stored secrets, read in the background when the secrets page lists them
+#[derive(Default)]
+pub struct SecretsView {
+	/// None until the first read came back
+	secrets: Option<Vec<SecretFile>>,
+	/// the secrets were asked for since they last changed
+	requested: bool,
+	loader: Loader<Vec<SecretFile>>,
+}
+
+impl SecretsView {
+	/// Read the secrets again on the next frame
+	pub fn reload(&mut self) {
+		self.requested = false;
+	}
+}
+
+/// Every secret in `Secrets/`, sorted by group and path
+pub fn list_secrets() -> Vec<SecretFile> {
+	let secrets_dir = match dotfiles_dir() {
+		Ok(p) => p.join("Secrets"),
+		Err(_) => return Vec::new(),
+	};
+	let Ok(entries) = secrets_dir.read_dir() else {
+		return Vec::new();
+	};
+
+	let mut secrets = Vec::new();
+	for entry in entries.flatten() {
+		let group = entry.file_name().to_string_lossy().into_owned();
+		let group_dir = entry.path();
+		for path in walk_files(&group_dir) {
+			let Some(target) = target_path(&group_dir, &path) else {
+				continue;
+			};
+			let metadata = fs::metadata(&path).ok();
+			secrets.push(SecretFile {
+				group: group.clone(),
+				size: metadata.as_ref().map_or(0, |m| m.len()),
+				modified: metadata.and_then(|m| m.modified().ok()),
+				path,
+				target,
+			});
+		}
+	}
+
+	secrets.sort_by(|a, b| (&a.group, &a.path).cmp(&(&b.group, &b.path)));
+	secrets
+}
+
+/// Every stored secret by group with its size, last change and target
+pub fn secrets_inventory(app: &mut TemplateApp, ui: &mut Ui) {
+	let view = &mut app.secrets;
+	if !view.requested {
+		view.loader.load(ui.ctx(), list_secrets);
+		view.requested = true;
+	}
+	if let Some(secrets) = view.loader.poll() {
+		view.secrets = Some(secrets);
+	}
+	view.loader.spinner(ui, "loading…");
+	let Some(secrets) = &view.secrets else {
+		return;
+	};
+	if secrets.is_empty() {
+		ui.label("No secrets stored");
+		return;
+	}
+
+	let total: u64 = secrets.iter().map(|s| s.size).sum();
+	ui.label(format!("{} secrets, {}", secrets.len(), format_size(total)));
+
+	egui::ScrollArea::vertical()
+		.id_source("secrets_inventory")
+		.max_height(250.0)
+		.show(ui, |ui| {
+			for group in secrets.chunk_by(|a, b| a.group == b.group) {
+				egui::CollapsingHeader::new(format!("{} ({})", group[0].group, group.len()))
+					.id_source(("secrets", &group[0].group))
+					.default_open(true)
+					.show(ui, |ui| {
+						egui::Grid::new(("secrets_grid", &group[0].group))
+							.striped(true)
+							.num_columns(4)
+							.show(ui, |ui| {
+								ui.strong("File");
+								ui.strong("Size");
+								ui.strong("Modified");
+								ui.strong("Decrypts to");
+								ui.end_row();
+
+								for secret in group {
+									let name = secret.path.file_name().unwrap_or_default().to_string_lossy();
+									ui.label(name).on_hover_text(secret.path.display().to_string());
+									ui.label(format_size(secret.size));
+									ui.label(secret.modified.map(format_time).unwrap_or_default());
+									ui.label(secret.target.display().to_string());
+									ui.end_row();
+								}
+							});
+					});
+			}
+		});
+}
+
+/// List of the secrets the last decrypt restored and where to
+pub fn restored_secrets(app: &TemplateApp, ui: &mut Ui) {
+	if app.restored_secrets.is_empty() {
+		return;
+	}
+
+	ui.label(format!("Restored {} secrets:", app.restored_secrets.len()));
+	egui::ScrollArea::vertical()
+		.id_source("restored_secrets")
+		.max_height(150.0)
+		.show(ui, |ui| {
+			egui::Grid::new("restored_secrets_grid").striped(true).show(ui, |ui| {
+				for (secret, target) in &app.restored_secrets {
+					ui.label(secret.display().to_string());
+					ui.label("→");
+					ui.label(target.display().to_string());
+					ui.end_row();
+				}
+			});
+		});
+}
+
+/// Masked passphrase prompt for a pending encrypt or decrypt command
+pub fn passphrase_prompt(app: &mut TemplateApp, ctx: &egui::Context) {
+	let encrypting = match &app.pending_secret {
+		Some(cli) => matches!(cli, Cli::Encrypt { .. }),
+		None => return,
+	};
+
+	let mut open = true;
+	let mut submit = false;
+	egui::Window::new("Passphrase")
+		.collapsible(false)
+		.resizable(false)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.open(&mut open)
+		.show(ctx, |ui| {
+			ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 10.0);
+
+			let input = ui.add(
+				TextEdit::singleline(&mut app.passphrase)
+					.password(true)
+					.hint_text("passphrase"),
+			);
+			if app.passphrase.is_empty() && !input.has_focus() {
+				input.request_focus();
+			}
+			if encrypting {
+				ui.add(
+					TextEdit::singleline(&mut app.passphrase_confirm)
+						.password(true)
+						.hint_text("repeat passphrase"),
+				);
+			}
+
+			let matching = !encrypting || app.passphrase == app.passphrase_confirm;
+			if !matching && !app.passphrase_confirm.is_empty() {
+				ui.colored_label(Color32::LIGHT_RED, "passphrases don't match");
+			}
+
+			let ready = !app.passphrase.is_empty() && matching;
+			let label = if encrypting { "Encrypt" } else { "Decrypt" };
+			submit = ui.add_enabled(ready, Button::new(label)).clicked()
+				|| (ready && ui.input(|i| i.key_pressed(Key::Enter)));
+		});
+
+	if submit {
+		if let Some(cli) = app.pending_secret.take() {
+			let command = match &cli {
+				Cli::Encrypt { .. } => "Encrypt",
+				_ => "Decrypt",
+			};
+			let entry = HistoryEntry::new(&cli);
+			let passphrase = app.passphrase.clone();
+			let (tx, rx) = mpsc::channel();
+			let job = Job::spawn(ctx, command, move || match cli {
+				Cli::Decrypt { groups, exclude } => {
+					let (output, code, restored) = decrypt_cmd_with_password(&groups, &exclude, &passphrase);
+					let _ = tx.send(restored);
+					(output, code)
+				}
+				cli => run_with_passphrase(cli, &passphrase),
+			})
+			.record(entry);
+			app.job = Some(job.then(move |app, _| {
+				app.secrets.reload();
+				// a failed decrypt might still have restored some of them
+				app.restored_secrets = rx.try_recv().unwrap_or_default();
+			}));
+		}
+	}
+	if submit || !open {
+		app.pending_secret = None;
+		app.passphrase.clear();
+		app.passphrase_confirm.clear();
+	}
+}
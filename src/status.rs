@@ -0,0 +1,280 @@
+use crate::app::TemplateApp;
+use crate::diff::DiffView;
+use crate::groups::{configs_dir, is_valid_target, target_path, walk_files};
+use crate::loader::Loader;
+use egui::{Color32, RichText, Ui};
+use egui_extras::{Column, TableBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Link state of a dotfile or a whole group
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LinkState {
+	Symlinked,
+	NotSymlinked,
+	/// something else is already at the target
+	Conflicting,
+	/// the group is for another OS
+	Incompatible,
+}
+
+impl LinkState {
+	pub fn badge(self) -> RichText {
+		let (text, color) = match self {
+			LinkState::Symlinked => ("symlinked", Color32::from_rgb(40, 120, 70)),
+			LinkState::NotSymlinked => ("not symlinked", Color32::from_rgb(90, 90, 100)),
+			LinkState::Conflicting => ("conflicting", Color32::from_rgb(150, 50, 50)),
+			LinkState::Incompatible => ("other OS", Color32::from_rgb(120, 95, 30)),
+		};
+		RichText::new(format!(" {text} "))
+			.color(Color32::WHITE)
+			.background_color(color)
+	}
+}
+
+/// A dotfile in `Configs/<group>` and where it is deployed to
+pub struct FileStatus {
+	pub path: PathBuf,
+	pub target: PathBuf,
+	pub state: LinkState,
+}
+
+pub struct GroupStatus {
+	pub group: String,
+	pub state: LinkState,
+	pub files: Vec<FileStatus>,
+}
+
+impl GroupStatus {
+	fn count(&self, state: LinkState) -> usize {
+		self.files.iter().filter(|f| f.state == state).count()
+	}
+}
+
+/// Compare the deployed target with the dotfile it should link to
+pub fn file_state(path: &Path, target: &Path) -> LinkState {
+	if target.symlink_metadata().is_err() {
+		return LinkState::NotSymlinked;
+	}
+	// the file itself or one of its parent directories links into the group
+	match (fs::canonicalize(path), fs::canonicalize(target)) {
+		(Ok(path), Ok(target)) if path == target => LinkState::Symlinked,
+		_ => LinkState::Conflicting,
+	}
+}
+
+/// Link state of every dotfile in `Configs/<group>`
+pub fn group_status(configs_dir: &Path, group: &str) -> GroupStatus {
+	let group_dir = configs_dir.join(group);
+	let valid = is_valid_target(group);
+	let files: Vec<_> = walk_files(&group_dir)
+		.into_iter()
+		.filter_map(|path| {
+			let target = target_path(&group_dir, &path)?;
+			let state = match valid {
+				true => file_state(&path, &target),
+				false => LinkState::Incompatible,
+			};
+			Some(FileStatus { path, target, state })
+		})
+		.collect();
+
+	let state = if !valid {
+		LinkState::Incompatible
+	} else if files.iter().any(|f| f.state == LinkState::Conflicting) {
+		LinkState::Conflicting
+	} else if !files.is_empty() && files.iter().all(|f| f.state == LinkState::Symlinked) {
+		LinkState::Symlinked
+	} else {
+		LinkState::NotSymlinked
+	};
+
+	GroupStatus {
+		group: group.into(),
+		state,
+		files,
+	}
+}
+
+/// Status of `groups`, `\*` is all groups
+pub fn load_status(groups: &[String]) -> Vec<GroupStatus> {
+	let Ok(configs_dir) = configs_dir() else {
+		return Vec::new();
+	};
+
+	let groups: Vec<String> = if groups.iter().any(|g| g == r"\*") {
+		configs_dir
+			.read_dir()
+			.into_iter()
+			.flatten()
+			.flatten()
+			.filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+			.filter_map(|e| e.file_name().into_string().ok())
+			.collect()
+	} else {
+		groups.to_vec()
+	};
+
+	groups.iter().map(|g| group_status(&configs_dir, g)).collect()
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum SortBy {
+	#[default]
+	Group,
+	State,
+	Linked,
+}
+
+/// State of the status page
+#[derive(Default)]
+pub struct StatusView {
+	/// None until loaded, reloaded when the selected groups change
+	groups: Option<Vec<GroupStatus>>,
+	/// The selected groups the status was last asked for, None to load again
+	loaded_for: Option<Vec<String>>,
+	loader: Loader<Vec<GroupStatus>>,
+	sort_by: SortBy,
+	descending: bool,
+	/// Group whose files are listed under the table
+	pub opened: Option<String>,
+}
+
+impl StatusView {
+	/// Load the status again on the next frame
+	pub fn reload(&mut self) {
+		self.loaded_for = None;
+	}
+
+	fn sort(&mut self) {
+		let Some(groups) = &mut self.groups else {
+			return;
+		};
+		match self.sort_by {
+			SortBy::Group => groups.sort_by(|a, b| a.group.cmp(&b.group)),
+			SortBy::State => groups.sort_by(|a, b| (a.state, &a.group).cmp(&(b.state, &b.group))),
+			SortBy::Linked => groups.sort_by_key(|g| g.count(LinkState::Symlinked)),
+		}
+		if self.descending {
+			groups.reverse();
+		}
+	}
+
+	fn header(&mut self, ui: &mut Ui, sort_by: SortBy, name: &str) {
+		let arrow = match (self.sort_by == sort_by, self.descending) {
+			(true, false) => " ⏶",
+			(true, true) => " ⏷",
+			(false, _) => "",
+		};
+		if ui
+			.selectable_label(self.sort_by == sort_by, format!("{name}{arrow}"))
+			.clicked()
+		{
+			self.descending = self.sort_by == sort_by && !self.descending;
+			self.sort_by = sort_by;
+			self.sort();
+		}
+	}
+}
+
+/// Sortable table of the link state of the selected groups
+pub fn status_table(app: &mut TemplateApp, ui: &mut Ui) {
+	let selected = app.groups.clone().unwrap_or(vec![r"\*".into()]);
+	let view = &mut app.status;
+	if view.loaded_for.as_ref() != Some(&selected) {
+		let groups = selected.clone();
+		view.loader.load(ui.ctx(), move || load_status(&groups));
+		view.loaded_for = Some(selected);
+	}
+	if let Some(groups) = view.loader.poll() {
+		view.groups = Some(groups);
+		view.sort();
+	}
+	view.loader.spinner(ui, "loading…");
+
+	let mut clicked = None;
+	ui.push_id("status_table", |ui| {
+		let row_height = ui.text_style_height(&egui::TextStyle::Body) + 6.0;
+		TableBuilder::new(ui)
+			.striped(true)
+			.sense(egui::Sense::click())
+			.max_scroll_height(200.0)
+			.column(Column::auto().at_least(150.0))
+			.column(Column::auto().at_least(120.0))
+			.column(Column::auto())
+			.column(Column::remainder())
+			.header(row_height, |mut header| {
+				header.col(|ui| view.header(ui, SortBy::Group, "Group"));
+				header.col(|ui| view.header(ui, SortBy::State, "State"));
+				header.col(|ui| view.header(ui, SortBy::Linked, "Linked"));
+				header.col(|ui| {
+					ui.strong("Conflicts");
+				});
+			})
+			.body(|mut body| {
+				for group in view.groups.iter().flatten() {
+					body.row(row_height, |mut row| {
+						row.set_selected(view.opened.as_ref() == Some(&group.group));
+						row.col(|ui| {
+							ui.label(&group.group);
+						});
+						row.col(|ui| {
+							ui.label(group.state.badge());
+						});
+						row.col(|ui| {
+							ui.label(format!("{}/{}", group.count(LinkState::Symlinked), group.files.len()));
+						});
+						row.col(|ui| {
+							ui.label(group.count(LinkState::Conflicting).to_string());
+						});
+						if row.response().clicked() {
+							clicked = Some(group.group.clone());
+						}
+					});
+				}
+			});
+	});
+
+	if let Some(group) = clicked {
+		view.opened = if view.opened.as_ref() == Some(&group) {
+			None
+		} else {
+			Some(group)
+		};
+	}
+
+	let Some(group) = view
+		.groups
+		.iter()
+		.flatten()
+		.find(|g| Some(&g.group) == view.opened.as_ref())
+	else {
+		return;
+	};
+
+	let mut diff = None;
+	egui::ScrollArea::vertical()
+		.id_source("status_files")
+		.max_height(150.0)
+		.show(ui, |ui| {
+			egui::Grid::new("status_files_grid").striped(true).show(ui, |ui| {
+				for file in &group.files {
+					ui.label(file.state.badge());
+					ui.label(file.target.display().to_string())
+						.on_hover_text(file.path.display().to_string());
+					// a real file is where the symlink should be
+					if file.state == LinkState::Conflicting
+						&& file.target.is_file()
+						&& ui.small_button("diff").clicked()
+					{
+						diff = Some(DiffView::open(ui.ctx(), &file.path, &file.target));
+					}
+					ui.end_row();
+				}
+			});
+		});
+
+	if diff.is_some() {
+		app.diff = diff;
+	}
+}
@@ -0,0 +1,227 @@
+use crate::app::TemplateApp;
+use crate::cmd::from_stow_cmd;
+use crate::groups::{dotfiles_dir, walk_files};
+use crate::worker::Job;
+use egui::{Color32, TextEdit, Ui};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Directories of a Stow repo that are never packages
+const NOT_PACKAGES: [&str; 3] = ["Configs", "Hooks", "Secrets"];
+
+/// A Stow package and the Tuckr group it will become
+pub struct StowPackage {
+	/// directory name in the Stow repo
+	name: String,
+	/// group name under `Configs/`, defaults to the package name
+	group: String,
+	/// leave the package where it is
+	skip: bool,
+	/// number of files in the package
+	files: usize,
+}
+
+/// State of the GNU Stow import wizard
+#[derive(Default)]
+pub struct StowWizard {
+	/// The Stow repo being converted
+	repo: Option<PathBuf>,
+	packages: Vec<StowPackage>,
+	/// why the repo can't be converted, tuckr only converts its own dotfiles directory
+	wrong_repo: Option<String>,
+}
+
+impl StowWizard {
+	fn open(&mut self, repo: PathBuf) {
+		self.packages = find_packages(&repo);
+		self.wrong_repo = match dotfiles_dir() {
+			Ok(dotfiles) if fs::canonicalize(&dotfiles).ok() == fs::canonicalize(&repo).ok() => None,
+			Ok(dotfiles) => Some(format!(
+				"tuckr only converts its dotfiles directory {}",
+				dotfiles.display()
+			)),
+			Err(e) => Some(e.to_string()),
+		};
+		self.repo = Some(repo);
+	}
+
+	/// Why the conversion can't run yet, if it can't
+	fn problem(&self) -> Option<String> {
+		let repo = self.repo.as_ref()?;
+		if self.wrong_repo.is_some() {
+			return self.wrong_repo.clone();
+		}
+		let mut groups = Vec::new();
+		for package in self.packages.iter().filter(|p| !p.skip) {
+			let group = package.group.trim();
+			if group.is_empty() || group.contains(['/', '\\']) || group.starts_with('.') {
+				return Some(format!("\"{group}\" is not a valid group name"));
+			}
+			if groups.contains(&group) {
+				return Some(format!("more than one package becomes \"{group}\""));
+			}
+			if repo.join("Configs").join(group).exists() {
+				return Some(format!("Configs/{group} already exists"));
+			}
+			groups.push(group);
+		}
+
+		if groups.is_empty() {
+			return Some("no packages to convert".into());
+		}
+		None
+	}
+}
+
+/// Convert the repo with tuckr's from-stow, then give packages their group names
+/// and move the skipped ones back out of `Configs/`
+fn convert(repo: &Path, packages: Vec<(String, String, bool)>) -> (String, ExitCode) {
+	let (mut output, code) = from_stow_cmd();
+	if code != ExitCode::SUCCESS {
+		return (output, code);
+	}
+
+	let configs = repo.join("Configs");
+	let mut code = ExitCode::SUCCESS;
+	let mut fail = |output: &mut String, from: &Path, e: std::io::Error| {
+		output.push_str(&format!("\ncouldn't move {}: {e}", from.display()));
+		code = ExitCode::FAILURE;
+	};
+
+	// every package is moved to a temporary name first so packages can take each others names,
+	// groups can't start with a dot so these never clash
+	let mut moving = Vec::new();
+	for (name, group, skip) in packages {
+		if !skip && group == name {
+			output.push_str(&format!("\n{name} → Configs/{group}"));
+			continue;
+		}
+		let from = configs.join(&name);
+		let temp = configs.join(format!(".from-stow-{name}"));
+		match fs::rename(&from, &temp) {
+			Ok(()) => moving.push((name, group, skip, temp)),
+			Err(e) => fail(&mut output, &from, e),
+		}
+	}
+
+	for (name, group, skip, temp) in moving {
+		let to = match skip {
+			true => repo.join(&name),
+			false => configs.join(&group),
+		};
+		match fs::rename(&temp, &to) {
+			Ok(()) if skip => output.push_str(&format!("\nleft {name} out")),
+			Ok(()) => output.push_str(&format!("\n{name} → Configs/{group}")),
+			Err(e) => fail(&mut output, &temp, e),
+		}
+	}
+	(output, code)
+}
+
+/// Top level directories of a Stow repo, hidden ones like `.git` are left out
+fn find_packages(repo: &Path) -> Vec<StowPackage> {
+	let Ok(entries) = repo.read_dir() else {
+		return Vec::new();
+	};
+
+	let mut packages: Vec<_> = entries
+		.flatten()
+		.filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+		.filter_map(|e| e.file_name().into_string().ok().map(|name| (name, e.path())))
+		.filter(|(name, _)| !name.starts_with('.') && !NOT_PACKAGES.contains(&name.as_str()))
+		.map(|(name, path)| StowPackage {
+			group: name.clone(),
+			name,
+			skip: false,
+			files: walk_files(&path).len(),
+		})
+		.collect();
+
+	packages.sort_by(|a, b| a.name.cmp(&b.name));
+	packages
+}
+
+/// Wizard that turns a GNU Stow repo into a Tuckr one
+pub fn stow_wizard(app: &mut TemplateApp, ui: &mut Ui) {
+	let folder_icon = egui::Image::new(crate::app::FOLDER_IMAGE).fit_to_original_size(1.05);
+	let wizard = &mut app.stow;
+
+	ui.horizontal(|ui| {
+		if ui
+			.add(egui::Button::image_and_text(folder_icon, "Open Stow repo…"))
+			.clicked()
+		{
+			let dialog = rfd::FileDialog::new();
+			let dialog = match dotfiles_dir() {
+				Ok(p) => dialog.set_directory(p),
+				Err(_) => dialog,
+			};
+			if let Some(repo) = dialog.pick_folder() {
+				wizard.open(repo);
+			}
+		}
+
+		if let Some(repo) = &wizard.repo {
+			ui.label(repo.display().to_string());
+		}
+	});
+
+	if wizard.repo.is_none() {
+		return;
+	}
+
+	if wizard.packages.is_empty() {
+		ui.label("No Stow packages found in this directory");
+		return;
+	}
+
+	egui::ScrollArea::vertical()
+		.id_source("stow_packages")
+		.max_height(200.0)
+		.show(ui, |ui| {
+			egui::Grid::new("stow_packages_grid")
+				.striped(true)
+				.num_columns(4)
+				.show(ui, |ui| {
+					ui.strong("Package");
+					ui.strong("Group");
+					ui.strong("Files");
+					ui.strong("Skip");
+					ui.end_row();
+
+					for package in &mut wizard.packages {
+						ui.label(&package.name);
+						ui.add_enabled(
+							!package.skip,
+							TextEdit::singleline(&mut package.group)
+								.desired_width(150.0)
+								.hint_text("group name"),
+						);
+						ui.label(package.files.to_string());
+						ui.checkbox(&mut package.skip, "");
+						ui.end_row();
+					}
+				});
+		});
+
+	match wizard.problem() {
+		Some(problem) => {
+			ui.colored_label(Color32::LIGHT_RED, problem);
+		}
+		None => {
+			if ui.button("Convert").clicked() {
+				let wizard = std::mem::take(&mut app.stow);
+				let repo = wizard.repo.unwrap_or_default();
+				let packages: Vec<_> = wizard
+					.packages
+					.into_iter()
+					.map(|p| (p.name, p.group.trim().to_string(), p.skip))
+					.collect();
+				let job = Job::spawn(ui.ctx(), "From Stow", move || convert(&repo, packages));
+				// Configs might not have been watched before
+				app.job = Some(job.then(|app, _| app.reload_groups = true));
+			}
+		}
+	}
+}
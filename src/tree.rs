@@ -0,0 +1,299 @@
+use crate::app::{Page, TemplateApp};
+use crate::format::format_size;
+use crate::groups::{configs_dir, is_valid_target, new_group_name, target_path};
+use crate::history::command_line;
+use crate::hooks::open_hook;
+use crate::loader::Loader;
+use crate::reorganize::{move_file, rename_group};
+use crate::status::{file_state, LinkState};
+use crate::worker::Job;
+use egui::{Button, Color32, RichText, Ui};
+use std::fs::{self, Metadata};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// A file or directory in `Configs/<group>`
+pub struct TreeNode {
+	name: String,
+	path: PathBuf,
+	/// where it's deployed to in the home directory
+	target: Option<PathBuf>,
+	state: LinkState,
+	size: u64,
+	mode: String,
+	text: bool,
+	/// None for files
+	children: Option<Vec<TreeNode>>,
+}
+
+#[cfg(unix)]
+fn mode(metadata: &Metadata) -> String {
+	use std::os::unix::fs::PermissionsExt;
+	let mode = metadata.permissions().mode();
+	let mut text = String::with_capacity(9);
+	for shift in [6, 3, 0] {
+		let bits = mode >> shift;
+		text.push(if bits & 4 != 0 { 'r' } else { '-' });
+		text.push(if bits & 2 != 0 { 'w' } else { '-' });
+		text.push(if bits & 1 != 0 { 'x' } else { '-' });
+	}
+	text
+}
+
+#[cfg(not(unix))]
+fn mode(metadata: &Metadata) -> String {
+	match metadata.permissions().readonly() {
+		true => "read only".into(),
+		false => "read write".into(),
+	}
+}
+
+/// If the start of the file has no nul bytes
+fn is_text(path: &Path) -> bool {
+	let mut start = [0; 8192];
+	match fs::File::open(path).and_then(|mut f| f.read(&mut start)) {
+		Ok(read) => !start[..read].contains(&0),
+		Err(_) => false,
+	}
+}
+
+/// Files and directories in `dir`, directories first
+fn read_tree(group_dir: &Path, dir: &Path, valid: bool) -> Vec<TreeNode> {
+	let mut nodes: Vec<_> = dir
+		.read_dir()
+		.into_iter()
+		.flatten()
+		.flatten()
+		.filter_map(|entry| {
+			let path = entry.path();
+			let metadata = entry.metadata().ok()?;
+			let target = target_path(group_dir, &path);
+			let children = metadata.is_dir().then(|| read_tree(group_dir, &path, valid));
+			let state = match (&target, valid) {
+				(_, false) => LinkState::Incompatible,
+				(Some(target), true) => file_state(&path, target),
+				(None, true) => LinkState::NotSymlinked,
+			};
+			// directories that aren't linked themselves are as linked as their contents
+			let state = match &children {
+				Some(children) if state == LinkState::Conflicting && !children.is_empty() => {
+					if children.iter().all(|c| c.state == LinkState::Symlinked) {
+						LinkState::Symlinked
+					} else if children.iter().any(|c| c.state == LinkState::Conflicting) {
+						LinkState::Conflicting
+					} else {
+						LinkState::NotSymlinked
+					}
+				}
+				_ => state,
+			};
+
+			Some(TreeNode {
+				name: entry.file_name().to_string_lossy().into_owned(),
+				target,
+				state,
+				size: match &children {
+					Some(children) => children.iter().map(|c| c.size).sum(),
+					None => metadata.len(),
+				},
+				mode: mode(&metadata),
+				text: children.is_none() && is_text(&path),
+				children,
+				path,
+			})
+		})
+		.collect();
+
+	nodes.sort_by(|a, b| (a.children.is_none(), &a.name).cmp(&(b.children.is_none(), &b.name)));
+	nodes
+}
+
+/// File tree of the group being looked at
+#[derive(Default)]
+pub struct GroupTree {
+	/// the group the tree was last asked for, None to read it again
+	group: Option<String>,
+	nodes: Vec<TreeNode>,
+	loader: Loader<Vec<TreeNode>>,
+	/// new name of the group while it's being renamed
+	rename: Option<String>,
+}
+
+/// What was clicked on a file
+enum NodeAction {
+	Edit(PathBuf),
+	/// move the file to another group
+	Move(PathBuf, String),
+}
+
+impl GroupTree {
+	/// Read the tree again on the next frame
+	pub fn reload(&mut self) {
+		self.group = None;
+	}
+}
+
+fn node_row(ui: &mut Ui, node: &TreeNode, groups: &[String], action: &mut Option<NodeAction>) {
+	ui.horizontal(|ui| {
+		ui.style_mut().spacing.item_spacing = egui::vec2(10.0, 5.0);
+		ui.label(node.state.badge());
+		ui.label(RichText::new(&node.mode).monospace());
+		ui.label(format_size(node.size));
+		if let Some(target) = &node.target {
+			ui.label(RichText::new(target.display().to_string()).color(Color32::GRAY));
+		}
+	});
+	if node.text && ui.small_button("edit").clicked() {
+		*action = Some(NodeAction::Edit(node.path.clone()));
+	}
+	if node.children.is_none() && !groups.is_empty() {
+		egui::ComboBox::from_id_source(("move", &node.path))
+			.selected_text("move to…")
+			.show_ui(ui, |ui| {
+				for group in groups {
+					if ui.selectable_label(false, group).clicked() {
+						*action = Some(NodeAction::Move(node.path.clone(), group.clone()));
+					}
+				}
+			});
+	}
+}
+
+fn show_nodes(ui: &mut Ui, nodes: &[TreeNode], groups: &[String], action: &mut Option<NodeAction>) {
+	for node in nodes {
+		match &node.children {
+			Some(children) => {
+				egui::CollapsingHeader::new(format!("{}/", node.name))
+					.id_source(&node.path)
+					.show(ui, |ui| {
+						node_row(ui, node, groups, action);
+						show_nodes(ui, children, groups, action);
+					});
+			}
+			None => {
+				ui.horizontal(|ui| {
+					ui.strong(&node.name);
+					node_row(ui, node, groups, action);
+				});
+			}
+		}
+	}
+}
+
+/// Point the excludes, saved profiles and history at the new name of a renamed group
+fn rename_references(app: &mut TemplateApp, old: &str, new: &str) {
+	let rename = |groups: &mut Vec<String>| {
+		for group in groups.iter_mut().filter(|g| *g == old) {
+			*group = new.to_string();
+		}
+	};
+	let rename_page = |page: &mut Page| match page {
+		Page::Add(Some(exclude), ..)
+		| Page::Rm(Some(exclude))
+		| Page::Set(Some(exclude), ..)
+		| Page::Decrypt(Some(exclude)) => rename(exclude),
+		_ => (),
+	};
+
+	app.exclude.iter_mut().for_each(rename);
+	rename_page(&mut app.page);
+	for profile in &mut app.profiles {
+		rename(&mut profile.groups);
+		rename(&mut profile.exclude);
+	}
+	for entry in &mut app.history {
+		rename(&mut entry.groups);
+		rename_page(&mut entry.page);
+		// shown as the command that is run again
+		if let Ok(cli) = entry.page.clone().into_cli(entry.groups.clone()) {
+			entry.command = command_line(&cli);
+		}
+	}
+}
+
+/// Collapsible tree of the files in the selected group with their targets
+pub fn group_tree(app: &mut TemplateApp, ui: &mut Ui) {
+	let Some(group) = app.groups.as_ref().and_then(|g| g.first()).cloned() else {
+		ui.label("Choose a group");
+		return;
+	};
+
+	let tree = &mut app.group_tree;
+	if tree.group.as_ref() != Some(&group) {
+		let read = group.clone();
+		tree.loader.load(ui.ctx(), move || match configs_dir() {
+			Ok(configs_dir) => {
+				let group_dir = configs_dir.join(&read);
+				read_tree(&group_dir, &group_dir, is_valid_target(&read))
+			}
+			Err(_) => Vec::new(),
+		});
+		tree.group = Some(group.clone());
+	}
+	if let Some(nodes) = tree.loader.poll() {
+		tree.nodes = nodes;
+	}
+	tree.loader.spinner(ui, "loading…");
+
+	let found_groups = app.found_groups.as_deref().unwrap_or_default();
+	let mut renamed = None;
+	ui.horizontal(|ui| {
+		ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+		let Some(name) = &mut tree.rename else {
+			if ui.button("Rename group").clicked() {
+				tree.rename = Some(group.clone());
+			}
+			return;
+		};
+
+		ui.add(egui::TextEdit::singleline(name).desired_width(120.0));
+		let new = new_group_name(name.trim(), None, found_groups);
+		if ui.add_enabled(new.is_ok(), Button::new("Rename")).clicked() {
+			renamed = new.clone().ok();
+		}
+		let cancel = ui.small_button("🗙").on_hover_text("cancel").clicked();
+		if let (Err(problem), false) = (new, name.trim() == group) {
+			ui.colored_label(Color32::LIGHT_RED, problem);
+		}
+		if cancel {
+			tree.rename = None;
+		}
+	});
+
+	let others: Vec<_> = found_groups.iter().filter(|g| **g != group).cloned().collect();
+	let mut action = None;
+	egui::ScrollArea::vertical()
+		.id_source("group_tree")
+		.max_height(250.0)
+		.show(ui, |ui| {
+			ui.style_mut().spacing.item_spacing = egui::vec2(5.0, 5.0);
+			show_nodes(ui, &tree.nodes, &others, &mut action);
+		});
+
+	if let Some(new) = renamed {
+		tree.rename = None;
+		let job = Job::spawn(ui.ctx(), "Rename", {
+			let (group, new) = (group.clone(), new.clone());
+			move || rename_group(&group, &new)
+		});
+		app.job = Some(job.then(move |app, code| {
+			if code == ExitCode::SUCCESS {
+				app.groups = Some(vec![new.clone()]);
+				rename_references(app, &group, &new);
+			}
+		}));
+	}
+
+	match action {
+		// text files are edited in the hooks editer
+		Some(NodeAction::Edit(path)) => {
+			open_hook(app, &path);
+			app.page = Page::Hooks;
+		}
+		Some(NodeAction::Move(path, to)) => {
+			app.job = Some(Job::spawn(ui.ctx(), "Move", move || move_file(&path, &to)));
+		}
+		None => (),
+	}
+}
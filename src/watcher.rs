@@ -0,0 +1,74 @@
+use crate::groups::dotfiles_dir;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+
+/// Part of the dotfiles directory that changed
+#[derive(Clone, Copy, PartialEq)]
+pub enum Changed {
+	Configs,
+	Hooks,
+	Secrets,
+}
+
+impl Changed {
+	fn of(dotfiles: &Path, path: &Path) -> Option<Self> {
+		let dir = path.strip_prefix(dotfiles).ok()?.components().next()?;
+		match dir.as_os_str().to_str()? {
+			"Configs" => Some(Changed::Configs),
+			"Hooks" => Some(Changed::Hooks),
+			"Secrets" => Some(Changed::Secrets),
+			_ => None,
+		}
+	}
+}
+
+/// Watches `Configs/`, `Hooks/` and `Secrets/` and repaints the ui when they change
+pub struct DotfilesWatcher {
+	/// stops watching when dropped
+	_watcher: RecommendedWatcher,
+	rx: Receiver<Changed>,
+}
+
+impl DotfilesWatcher {
+	/// None if there's no dotfiles directory to watch
+	pub fn new(ctx: &egui::Context) -> Option<Self> {
+		let dotfiles = dotfiles_dir().ok()?;
+		let (tx, rx) = mpsc::channel();
+		let ctx = ctx.clone();
+		let root = dotfiles.clone();
+
+		let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+			let Ok(event) = event else {
+				return;
+			};
+			if matches!(event.kind, EventKind::Access(_)) {
+				return;
+			}
+
+			for changed in event.paths.iter().filter_map(|p| Changed::of(&root, p)) {
+				let _ = tx.send(changed);
+			}
+			ctx.request_repaint();
+		})
+		.ok()?;
+
+		for dir in ["Configs", "Hooks", "Secrets"] {
+			// directories that don't exist yet are picked up by the next refresh
+			let _ = watcher.watch(&dotfiles.join(dir), RecursiveMode::Recursive);
+		}
+
+		Some(Self { _watcher: watcher, rx })
+	}
+
+	/// What changed since the last call, each part at most once
+	pub fn changes(&self) -> Vec<Changed> {
+		let mut changes = Vec::new();
+		for changed in self.rx.try_iter() {
+			if !changes.contains(&changed) {
+				changes.push(changed);
+			}
+		}
+		changes
+	}
+}
@@ -0,0 +1,194 @@
+use crate::app::TemplateApp;
+use crate::history::{record, HistoryEntry};
+use egui::{Button, Ui};
+use std::io;
+use std::process::{Child, ExitCode, ExitStatus};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Called on the ui thread with the exit code once a job finished
+type Then = Box<dyn FnOnce(&mut TemplateApp, ExitCode)>;
+
+#[derive(Default)]
+struct Processes {
+	cancelled: bool,
+	/// started by the job and still running
+	children: Vec<Child>,
+}
+
+/// Stops a job, the worker thread checks it between steps
+#[derive(Clone, Default)]
+pub struct Cancel(Arc<Mutex<Processes>>);
+
+impl Cancel {
+	fn lock(&self) -> MutexGuard<'_, Processes> {
+		self.0.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Kill the processes the job is waiting on and stop it before its next step
+	pub fn cancel(&self) {
+		let mut processes = self.lock();
+		processes.cancelled = true;
+		for child in &mut processes.children {
+			let _ = child.kill();
+		}
+	}
+
+	pub fn cancelled(&self) -> bool {
+		self.lock().cancelled
+	}
+
+	/// Wait for `child` to exit, it's killed if the job is cancelled meanwhile
+	pub fn wait(&self, mut child: Child) -> io::Result<ExitStatus> {
+		let id = child.id();
+		{
+			let mut processes = self.lock();
+			if processes.cancelled {
+				let _ = child.kill();
+			}
+			processes.children.push(child);
+		}
+
+		loop {
+			{
+				let mut processes = self.lock();
+				let i = processes
+					.children
+					.iter()
+					.position(|c| c.id() == id)
+					.expect("only this wait removes the child");
+				// the status of an exited child is kept, so this wait doesn't block
+				if !matches!(processes.children[i].try_wait(), Ok(None)) {
+					return processes.children.remove(i).wait();
+				}
+			}
+			thread::sleep(Duration::from_millis(50));
+		}
+	}
+}
+
+/// A tuckr command running on a worker thread
+pub struct Job {
+	/// Name of the running command
+	pub command: String,
+	started: Instant,
+	rx: Receiver<(String, ExitCode)>,
+	then: Option<Then>,
+	/// added to the history with the output once the command finished
+	entry: Option<HistoryEntry>,
+	/// None if the job can't be stopped midway
+	cancel: Option<Cancel>,
+	/// cancel was clicked, the job is kept until it actually stopped
+	cancelling: bool,
+}
+
+impl Job {
+	/// Run `f` on a worker thread, the ui is repainted once it's done
+	pub fn spawn(
+		ctx: &egui::Context, command: impl Into<String>, f: impl FnOnce() -> (String, ExitCode) + Send + 'static,
+	) -> Self {
+		let (tx, rx) = mpsc::channel();
+		let ctx = ctx.clone();
+		thread::spawn(move || {
+			// the app might have been closed and the receiver dropped
+			let _ = tx.send(f());
+			ctx.request_repaint();
+		});
+
+		Self {
+			command: command.into(),
+			started: Instant::now(),
+			rx,
+			then: None,
+			entry: None,
+			cancel: None,
+			cancelling: false,
+		}
+	}
+
+	/// Let the cancel button stop the job through `cancel`
+	pub fn cancellable(mut self, cancel: Cancel) -> Self {
+		self.cancel = Some(cancel);
+		self
+	}
+
+	/// Run `then` with the app after the command finished
+	pub fn then(mut self, then: impl FnOnce(&mut TemplateApp, ExitCode) + 'static) -> Self {
+		self.then = Some(Box::new(then));
+		self
+	}
+
+	/// Keep the command in the history once it finished
+	pub fn record(mut self, entry: Option<HistoryEntry>) -> Self {
+		self.entry = entry;
+		self
+	}
+}
+
+/// Take the output of the running job if it finished
+pub fn poll_job(app: &mut TemplateApp) {
+	let Some(job) = &app.job else {
+		return;
+	};
+
+	match job.rx.try_recv() {
+		Ok((output, code)) => {
+			let Some(job) = app.job.take() else {
+				return;
+			};
+			let output = match job.cancelling {
+				true => format!("{} cancelled\n{output}", job.command),
+				false => output,
+			};
+			if let Some(mut entry) = job.entry {
+				entry.success = code == ExitCode::SUCCESS;
+				entry.output.clone_from(&output);
+				record(app, entry);
+			}
+			app.output = output;
+			app.status.reload();
+			app.add_preview.reload();
+			app.group_tree.reload();
+			app.archive.reload();
+			// nothing was watched yet, e.g. before init created the dotfiles
+			if app.watcher.is_none() {
+				app.reload_groups = true;
+			}
+			if let Some(then) = job.then {
+				then(app, code);
+			}
+		}
+		Err(TryRecvError::Empty) => (),
+		Err(TryRecvError::Disconnected) => {
+			app.output = format!("{} failed unexpectedly", job.command);
+			app.job = None;
+		}
+	}
+}
+
+/// Spinner and cancel button while a job is running
+pub fn job_progress(app: &mut TemplateApp, ui: &mut Ui) {
+	let Some(job) = &mut app.job else {
+		return;
+	};
+
+	ui.horizontal(|ui| {
+		ui.add(egui::Spinner::new());
+		let elapsed = job.started.elapsed().as_secs();
+		if job.cancelling {
+			ui.label(format!("Cancelling {}… {elapsed}s", job.command));
+			return;
+		}
+		ui.label(format!("Running {}… {elapsed}s", job.command));
+
+		// the controls stay disabled until the worker thread returned
+		if let Some(cancel) = &job.cancel {
+			if ui.add(Button::new("Cancel")).clicked() {
+				cancel.cancel();
+				job.cancelling = true;
+			}
+		}
+	});
+}